@@ -0,0 +1,2751 @@
+//! JSON API described with OpenAPI. `config_router` serves the spec at
+//! `/api/openapi.json` and a Swagger UI at `/api/docs`.
+
+// Triggered by code generated by `#[derive(Union)]`.
+#![allow(clippy::match_result_ok)]
+
+use chrono::{DateTime, Utc};
+use poem::http::Uri;
+use poem::session::Session;
+use poem::web::Data;
+use poem::Request;
+use poem_openapi::param::{Path, Query};
+use poem_openapi::payload::{Form, Json};
+use poem_openapi::types::{MaybeUndefined, ParseFromJSON, ToJSON};
+use poem_openapi::{ApiRequest, ApiResponse, Enum, Object, OpenApi, OpenApiService, Tags, Union};
+use sea_orm::ActiveEnum;
+use serde::Deserialize;
+
+use crate::auth::{self, viewed_by, CurrentAuthor};
+use crate::diff::{self, Diff};
+use crate::domain::{api_token, article, author, comment, outbox, publication, revision, tag};
+use crate::error::{Error, Problem};
+use crate::pagination::{Cursor, KeysetParams, Page, Params, Slice};
+use crate::repositories::{
+    ArticleFilter, ArticleUpdate, AuthorCreate, AuthorUpdate, CommentCreate,
+};
+use crate::slug;
+use crate::AppStateM;
+
+#[derive(Tags)]
+enum ApiTags {
+    /// Articles
+    Articles,
+    /// Authors
+    Authors,
+    /// Tags
+    Tags,
+    /// Comments
+    Comments,
+    /// Logging in
+    Auth,
+    /// API tokens
+    Tokens,
+    /// Events waiting for the publishers
+    Outbox,
+}
+
+#[derive(Enum, Clone, Copy)]
+#[oai(rename_all = "snake_case")]
+pub enum ArticleStatus {
+    Draft,
+    InReview,
+    Published,
+    Archived,
+}
+
+impl From<article::Status> for ArticleStatus {
+    fn from(s: article::Status) -> Self {
+        match s {
+            article::Status::Draft => ArticleStatus::Draft,
+            article::Status::InReview => ArticleStatus::InReview,
+            article::Status::Published => ArticleStatus::Published,
+            article::Status::Archived => ArticleStatus::Archived,
+        }
+    }
+}
+
+impl From<ArticleStatus> for article::Status {
+    fn from(s: ArticleStatus) -> Self {
+        match s {
+            ArticleStatus::Draft => article::Status::Draft,
+            ArticleStatus::InReview => article::Status::InReview,
+            ArticleStatus::Published => article::Status::Published,
+            ArticleStatus::Archived => article::Status::Archived,
+        }
+    }
+}
+
+#[derive(Enum, Clone, Copy, Deserialize)]
+#[oai(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorRole {
+    Admin,
+    Editor,
+    Author,
+    Reader,
+}
+
+impl From<author::Role> for AuthorRole {
+    fn from(r: author::Role) -> Self {
+        match r {
+            author::Role::Admin => AuthorRole::Admin,
+            author::Role::Editor => AuthorRole::Editor,
+            author::Role::Author => AuthorRole::Author,
+            author::Role::Reader => AuthorRole::Reader,
+        }
+    }
+}
+
+impl From<AuthorRole> for author::Role {
+    fn from(r: AuthorRole) -> Self {
+        match r {
+            AuthorRole::Admin => author::Role::Admin,
+            AuthorRole::Editor => author::Role::Editor,
+            AuthorRole::Author => author::Role::Author,
+            AuthorRole::Reader => author::Role::Reader,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct Article {
+    pub id: i32,
+    pub title: String,
+    /// Unique, derived from the title. Old slugs still resolve.
+    pub slug: String,
+    /// The HTML page of a published article.
+    pub permalink: Option<String>,
+    /// Markdown, with GFM tables and footnotes.
+    pub content: Option<String>,
+    /// `content` rendered to sanitized HTML.
+    pub content_html: Option<String>,
+    pub author_id: Option<i32>,
+    pub status: ArticleStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    /// When a scheduled article will be published.
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+impl From<article::Model> for Article {
+    fn from(m: article::Model) -> Self {
+        Self {
+            permalink: m.permalink(),
+            id: m.id,
+            title: m.title,
+            slug: m.slug,
+            content: m.content,
+            content_html: m.content_html,
+            author_id: m.author_id,
+            status: m.status.into(),
+            published_at: m.published_at,
+            publish_at: m.publish_at,
+        }
+    }
+}
+
+/// Drafts go to review or are published, published articles are archived and
+/// archived ones can be reopened as drafts.
+#[derive(Object)]
+pub struct StatusChange {
+    pub status: ArticleStatus,
+}
+
+/// `null` cancels the schedule.
+#[derive(Object)]
+pub struct Schedule {
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// An article with its author embedded.
+#[derive(Object)]
+pub struct ArticleWithAuthor {
+    #[oai(flatten)]
+    pub article: Article,
+    pub author: Option<Author>,
+}
+
+impl From<article::WithAuthor> for ArticleWithAuthor {
+    fn from(m: article::WithAuthor) -> Self {
+        Self {
+            article: m.article.into(),
+            author: m.author.map(Into::into),
+        }
+    }
+}
+
+/// A search match. `snippet` is HTML with the matched terms in `<mark>`.
+#[derive(Object)]
+pub struct SearchResult {
+    #[oai(flatten)]
+    pub article: Article,
+    pub score: f64,
+    pub snippet: String,
+}
+
+impl From<article::SearchHit> for SearchResult {
+    fn from(hit: article::SearchHit) -> Self {
+        Self {
+            article: hit.article.into(),
+            score: hit.score,
+            snippet: hit.snippet,
+        }
+    }
+}
+
+/// `author_id` defaults to the logged in author.
+#[derive(Object, Deserialize)]
+pub struct NewArticle {
+    pub title: String,
+    /// Markdown.
+    pub content: Option<String>,
+    pub author_id: Option<i32>,
+}
+
+/// The logged in author is recorded as the editor in the revision history.
+#[derive(Object)]
+pub struct ArticleReplace {
+    pub title: String,
+    pub content: Option<String>,
+}
+
+/// Absent fields are left untouched, `"content": null` clears the content.
+#[derive(Object)]
+pub struct ArticlePatch {
+    pub title: Option<String>,
+    pub content: MaybeUndefined<String>,
+}
+
+#[derive(Object)]
+pub struct Revision {
+    pub rev: i32,
+    pub title: String,
+    pub content: Option<String>,
+    pub content_html: Option<String>,
+    pub editor_id: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<revision::Model> for Revision {
+    fn from(m: revision::Model) -> Self {
+        Self {
+            rev: m.rev,
+            title: m.title,
+            content: m.content,
+            content_html: m.content_html,
+            editor_id: m.editor_id,
+            created_at: m.created_at,
+        }
+    }
+}
+
+#[derive(Enum, Clone, Copy)]
+#[oai(rename_all = "lowercase")]
+pub enum DiffFormat {
+    Unified,
+    Words,
+}
+
+impl From<DiffFormat> for diff::Format {
+    fn from(f: DiffFormat) -> Self {
+        match f {
+            DiffFormat::Unified => diff::Format::Unified,
+            DiffFormat::Words => diff::Format::Words,
+        }
+    }
+}
+
+#[derive(Enum)]
+#[oai(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Object)]
+pub struct DiffChange {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+impl From<diff::Change> for DiffChange {
+    fn from(c: diff::Change) -> Self {
+        Self {
+            op: match c.op {
+                diff::Op::Equal => DiffOp::Equal,
+                diff::Op::Insert => DiffOp::Insert,
+                diff::Op::Delete => DiffOp::Delete,
+            },
+            text: c.text,
+        }
+    }
+}
+
+/// The title and content of two revisions compared as one document: the
+/// title, a blank line, then the content. Only the field for the requested
+/// format is set.
+#[derive(Object)]
+pub struct RevisionDiff {
+    pub from: i32,
+    pub to: i32,
+    #[oai(skip_serializing_if_is_none)]
+    pub unified: Option<String>,
+    #[oai(skip_serializing_if_is_none)]
+    pub changes: Option<Vec<DiffChange>>,
+}
+
+impl RevisionDiff {
+    fn new(from: i32, to: i32, diff: Diff) -> Self {
+        let (unified, changes) = match diff {
+            Diff::Unified(unified) => (Some(unified), None),
+            Diff::Words(changes) => (None, Some(changes.into_iter().map(Into::into).collect())),
+        };
+        Self {
+            from,
+            to,
+            unified,
+            changes,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct Author {
+    pub id: i32,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub role: AuthorRole,
+}
+
+impl From<author::Model> for Author {
+    fn from(m: author::Model) -> Self {
+        Self {
+            id: m.id,
+            first_name: m.first_name,
+            last_name: m.last_name,
+            email: m.email,
+            role: m.role.into(),
+        }
+    }
+}
+
+/// Without a `password` the author can't log in. When replacing an author,
+/// a missing password or role keeps the current one.
+#[derive(Object, Deserialize)]
+pub struct NewAuthor {
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    /// At least 8 characters.
+    #[oai(write_only)]
+    pub password: Option<String>,
+    /// `author` for new authors; only admins give roles.
+    pub role: Option<AuthorRole>,
+}
+
+#[derive(Object)]
+pub struct AuthorPatch {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    #[oai(write_only)]
+    pub password: Option<String>,
+    pub role: Option<AuthorRole>,
+}
+
+#[derive(Object)]
+pub struct Credentials {
+    pub email: String,
+    #[oai(write_only)]
+    pub password: String,
+}
+
+#[derive(Enum, Clone, Copy)]
+pub enum TokenScope {
+    #[oai(rename = "articles:read")]
+    ArticlesRead,
+    #[oai(rename = "articles:write")]
+    ArticlesWrite,
+    #[oai(rename = "admin")]
+    Admin,
+}
+
+impl From<api_token::Scope> for TokenScope {
+    fn from(s: api_token::Scope) -> Self {
+        match s {
+            api_token::Scope::ArticlesRead => TokenScope::ArticlesRead,
+            api_token::Scope::ArticlesWrite => TokenScope::ArticlesWrite,
+            api_token::Scope::Admin => TokenScope::Admin,
+        }
+    }
+}
+
+impl From<TokenScope> for api_token::Scope {
+    fn from(s: TokenScope) -> Self {
+        match s {
+            TokenScope::ArticlesRead => api_token::Scope::ArticlesRead,
+            TokenScope::ArticlesWrite => api_token::Scope::ArticlesWrite,
+            TokenScope::Admin => api_token::Scope::Admin,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct ApiToken {
+    pub id: i32,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<api_token::Model> for ApiToken {
+    fn from(m: api_token::Model) -> Self {
+        Self {
+            id: m.id,
+            scopes: m.scopes().into_iter().map(Into::into).collect(),
+            name: m.name,
+            created_at: m.created_at,
+            expires_at: m.expires_at,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct NewToken {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// 30 by default, at most 365.
+    #[oai(default = "default_token_days")]
+    pub expires_in_days: i64,
+}
+
+fn default_token_days() -> i64 {
+    30
+}
+
+/// The `secret` is sent as `Authorization: Bearer <secret>`. It is not
+/// stored and can't be shown again.
+#[derive(Object)]
+pub struct MintedToken {
+    #[oai(flatten)]
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+#[derive(Enum, Clone, Copy)]
+#[oai(rename_all = "snake_case")]
+pub enum OutboxState {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+impl From<outbox::State> for OutboxState {
+    fn from(s: outbox::State) -> Self {
+        match s {
+            outbox::State::Pending => OutboxState::Pending,
+            outbox::State::Delivered => OutboxState::Delivered,
+            outbox::State::Dead => OutboxState::Dead,
+        }
+    }
+}
+
+impl From<OutboxState> for outbox::State {
+    fn from(s: OutboxState) -> Self {
+        match s {
+            OutboxState::Pending => outbox::State::Pending,
+            OutboxState::Delivered => outbox::State::Delivered,
+            OutboxState::Dead => outbox::State::Dead,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct OutboxEntry {
+    pub id: i32,
+    /// Only `article.published` so far.
+    pub event: String,
+    pub article_id: i32,
+    pub state: OutboxState,
+    /// Failed deliveries so far.
+    pub attempts: i32,
+    /// When a pending entry is tried next.
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<outbox::Model> for OutboxEntry {
+    fn from(m: outbox::Model) -> Self {
+        Self {
+            id: m.id,
+            event: m.event.to_value(),
+            article_id: m.article_id,
+            state: m.state.into(),
+            attempts: m.attempts,
+            next_attempt_at: m.next_attempt_at,
+            last_error: m.last_error,
+            created_at: m.created_at,
+            delivered_at: m.delivered_at,
+        }
+    }
+}
+
+#[derive(Enum, Clone, Copy)]
+#[oai(rename_all = "snake_case")]
+pub enum PublicationState {
+    Posted,
+    Failed,
+}
+
+impl From<publication::State> for PublicationState {
+    fn from(s: publication::State) -> Self {
+        match s {
+            publication::State::Posted => PublicationState::Posted,
+            publication::State::Failed => PublicationState::Failed,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct Publication {
+    pub channel: String,
+    pub state: PublicationState,
+    pub remote_id: Option<String>,
+    pub remote_url: Option<String>,
+    /// Every try, successful or not.
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    /// When the article was last posted successfully.
+    pub posted_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<publication::Model> for Publication {
+    fn from(m: publication::Model) -> Self {
+        Self {
+            channel: m.channel,
+            state: m.state.into(),
+            remote_id: m.remote_id,
+            remote_url: m.remote_url,
+            attempts: m.attempts,
+            last_error: m.last_error,
+            posted_at: m.posted_at,
+            updated_at: m.updated_at,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct Tag {
+    pub slug: String,
+    pub name: String,
+}
+
+impl From<tag::Model> for Tag {
+    fn from(m: tag::Model) -> Self {
+        Self {
+            slug: m.slug,
+            name: m.name,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct TagCount {
+    pub slug: String,
+    pub name: String,
+    pub article_count: i64,
+}
+
+impl From<tag::WithCount> for TagCount {
+    fn from(m: tag::WithCount) -> Self {
+        Self {
+            slug: m.slug,
+            name: m.name,
+            article_count: m.article_count,
+        }
+    }
+}
+
+/// The tag name is normalized: "Rust" and "rust" are the same tag.
+#[derive(Object)]
+pub struct NewTag {
+    pub name: String,
+}
+
+#[derive(Object)]
+pub struct Comment {
+    pub id: i32,
+    pub article_id: i32,
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<comment::Model> for Comment {
+    fn from(m: comment::Model) -> Self {
+        Self {
+            id: m.id,
+            article_id: m.article_id,
+            parent_id: m.parent_id,
+            author_name: m.author_name,
+            body: m.body,
+            created_at: m.created_at,
+        }
+    }
+}
+
+/// A comment with its replies, oldest first.
+#[derive(Object)]
+pub struct CommentThread {
+    #[oai(flatten)]
+    pub comment: Comment,
+    pub replies: Vec<CommentThread>,
+}
+
+impl From<comment::Thread> for CommentThread {
+    fn from(t: comment::Thread) -> Self {
+        Self {
+            comment: t.comment.into(),
+            replies: t.replies.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Set `parent_id` to reply to another comment on the same article.
+#[derive(Object)]
+pub struct NewComment {
+    pub author_name: String,
+    pub body: String,
+    pub parent_id: Option<i32>,
+}
+
+#[derive(Object)]
+pub struct CommentEdit {
+    pub body: String,
+}
+
+#[derive(ApiRequest)]
+pub enum CreateArticleRequest {
+    Json(Json<NewArticle>),
+    Form(Form<NewArticle>),
+}
+
+#[derive(ApiRequest)]
+pub enum CreateAuthorRequest {
+    Json(Json<NewAuthor>),
+    Form(Form<NewAuthor>),
+}
+
+/// Error responses, sent as RFC 7807 problem details.
+#[derive(ApiResponse)]
+pub enum ApiError {
+    /// The request could not be parsed.
+    #[oai(status = 400, content_type = "application/problem+json")]
+    BadRequest(Json<Problem>),
+    /// Authentication is required.
+    #[oai(status = 401, content_type = "application/problem+json")]
+    Unauthorized(Json<Problem>),
+    /// Not allowed for the logged in author or token.
+    #[oai(status = 403, content_type = "application/problem+json")]
+    Forbidden(Json<Problem>),
+    /// The resource does not exist.
+    #[oai(status = 404, content_type = "application/problem+json")]
+    NotFound(Json<Problem>),
+    /// The request conflicts with existing data.
+    #[oai(status = 409, content_type = "application/problem+json")]
+    Conflict(Json<Problem>),
+    /// The request is well-formed but invalid.
+    #[oai(status = 422, content_type = "application/problem+json")]
+    Validation(Json<Problem>),
+    /// The server failed to handle the request.
+    #[oai(status = 500, content_type = "application/problem+json")]
+    Internal(Json<Problem>),
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let problem = Json(e.problem());
+        match e {
+            Error::NotFound(_) => ApiError::NotFound(problem),
+            Error::Validation(_) => ApiError::Validation(problem),
+            Error::Conflict(_) => ApiError::Conflict(problem),
+            Error::Unauthorized => ApiError::Unauthorized(problem),
+            Error::Forbidden(_) => ApiError::Forbidden(problem),
+            Error::Internal(_) => ApiError::Internal(problem),
+        }
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+#[derive(ApiResponse)]
+pub enum BySlug {
+    #[oai(status = 200)]
+    Ok(Json<Box<ArticleWithAuthor>>),
+    /// An old slug; `Location` points at the current one.
+    #[oai(status = 301)]
+    MovedPermanently(#[oai(header = "Location")] String),
+}
+
+#[derive(ApiResponse)]
+pub enum Created<T: poem_openapi::types::ToJSON> {
+    #[oai(status = 201)]
+    Created(Json<T>),
+}
+
+#[derive(ApiResponse)]
+pub enum Deleted {
+    #[oai(status = 204)]
+    NoContent,
+}
+
+#[derive(ApiResponse)]
+pub enum LoggedOut {
+    #[oai(status = 204)]
+    NoContent,
+}
+
+/// One page of a list. `next` and `prev` are absent on the last and first page.
+#[derive(Object)]
+pub struct Paged<T: ParseFromJSON + ToJSON> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub page_size: u64,
+    pub total_items: u64,
+    pub total_pages: u64,
+    pub next: Option<String>,
+    pub prev: Option<String>,
+}
+
+/// A keyset slice of a list. Send `next_cursor` back as `after` to continue;
+/// it is absent on the last slice.
+#[derive(Object)]
+pub struct Sliced<T: ParseFromJSON + ToJSON> {
+    pub items: Vec<T>,
+    pub limit: u64,
+    pub next_cursor: Option<String>,
+    pub next: Option<String>,
+}
+
+/// Articles are listed by page, or by cursor when `after` or `limit` is given.
+#[derive(Union)]
+#[oai(one_of)]
+pub enum ArticleListing {
+    Page(Paged<Article>),
+    Slice(Sliced<Article>),
+}
+
+#[derive(ApiResponse)]
+pub enum Listing<T: ToJSON> {
+    /// The `Link` header repeats the navigation links as in RFC 8288.
+    #[oai(status = 200)]
+    Ok(Json<T>, #[oai(header = "Link")] Option<String>),
+}
+
+const PAGING_PARAMS: [&str; 4] = ["page", "page_size", "after", "limit"];
+
+/// The request URL with new paging parameters, other query parameters are kept.
+fn list_url(uri: &Uri, paging: &[(&str, String)]) -> String {
+    let mut query: Vec<String> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            !pair.is_empty() && !PAGING_PARAMS.contains(&name)
+        })
+        .map(str::to_owned)
+        .collect();
+    query.extend(paging.iter().map(|(name, value)| format!("{name}={value}")));
+    format!("{}?{}", uri.path(), query.join("&"))
+}
+
+fn link_header(links: &[(&str, &Option<String>)]) -> Option<String> {
+    let links: Vec<String> = links
+        .iter()
+        .filter_map(|(rel, url)| url.as_ref().map(|url| format!("<{url}>; rel=\"{rel}\"")))
+        .collect();
+    (!links.is_empty()).then(|| links.join(", "))
+}
+
+impl<T: ParseFromJSON + ToJSON> Paged<T> {
+    /// Also returns the matching `Link` header.
+    fn new<M>(uri: &Uri, page: Page<M>) -> (Self, Option<String>)
+    where
+        T: From<M>,
+    {
+        let url = |page_no: u64| {
+            list_url(
+                uri,
+                &[
+                    ("page", page_no.to_string()),
+                    ("page_size", page.page_size.to_string()),
+                ],
+            )
+        };
+        let next = page.next_page().map(url);
+        let prev = page.prev_page().map(url);
+        let link = link_header(&[
+            ("first", &Some(url(1))),
+            ("prev", &prev),
+            ("next", &next),
+            ("last", &Some(url(page.total_pages.max(1)))),
+        ]);
+        let page = page.map(T::from);
+        let paged = Self {
+            items: page.items,
+            page: page.page,
+            page_size: page.page_size,
+            total_items: page.total_items,
+            total_pages: page.total_pages,
+            next,
+            prev,
+        };
+        (paged, link)
+    }
+}
+
+impl<T: ParseFromJSON + ToJSON> Sliced<T> {
+    /// Also returns the matching `Link` header.
+    fn new<M>(uri: &Uri, slice: Slice<M>) -> (Self, Option<String>)
+    where
+        T: From<M>,
+    {
+        let limit = ("limit", slice.limit.to_string());
+        let next_cursor = slice.next.map(|cursor| cursor.encode());
+        let next = next_cursor
+            .as_ref()
+            .map(|cursor| list_url(uri, &[("after", cursor.clone()), limit.clone()]));
+        let link = link_header(&[
+            ("first", &Some(list_url(uri, std::slice::from_ref(&limit)))),
+            ("next", &next),
+        ]);
+        let slice = slice.map(T::from);
+        let sliced = Self {
+            items: slice.items,
+            limit: slice.limit,
+            next_cursor,
+            next,
+        };
+        (sliced, link)
+    }
+}
+
+fn default_diff_format() -> DiffFormat {
+    DiffFormat::Unified
+}
+
+pub struct ArticleApi;
+
+#[OpenApi(tag = "ApiTags::Articles")]
+impl ArticleApi {
+    /// Create an article
+    ///
+    /// Like every change to articles, this needs a logged in author.
+    #[oai(path = "/articles", method = "post")]
+    async fn create_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        body: CreateArticleRequest,
+    ) -> ApiResult<Created<Article>> {
+        let (CreateArticleRequest::Json(Json(new)) | CreateArticleRequest::Form(Form(new))) = body;
+        let author_id = new.author_id.unwrap_or(author.0.id);
+        let created = state
+            .service
+            .create_article(&new.title, new.content, Some(author_id), &author.0)
+            .await?;
+        Ok(Created::Created(Json(article::Model::from(created).into())))
+    }
+
+    /// List articles
+    ///
+    /// Either by page, with `page` (from 1) and `page_size`, or by cursor, with
+    /// `after` and `limit`; the two modes can't be mixed. Out of range sizes
+    /// are clamped. `tag` keeps only the articles with that tag. Only
+    /// published articles are listed unless another `status` is asked for,
+    /// which needs a login: authors see their own articles, editors and
+    /// admins everyone's.
+    #[oai(path = "/articles", method = "get")]
+    #[allow(clippy::too_many_arguments)]
+    async fn list_articles(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        req: &Request,
+        page: Query<Option<i64>>,
+        page_size: Query<Option<i64>>,
+        after: Query<Option<String>>,
+        limit: Query<Option<i64>>,
+        tag: Query<Option<String>>,
+        status: Query<Option<ArticleStatus>>,
+    ) -> ApiResult<Listing<ArticleListing>> {
+        let filter = ArticleFilter {
+            tag: tag.0,
+            status: Some(status.0.map_or(article::Status::Published, Into::into)),
+            ..Default::default()
+        };
+        if after.is_none() && limit.is_none() {
+            let params = Params::new(page.0, page_size.0);
+            let articles = state
+                .service
+                .list_articles(&filter, params, viewed_by(&viewer))
+                .await?;
+            let (paged, link) = Paged::new(req.uri(), articles);
+            return Ok(Listing::Ok(Json(ArticleListing::Page(paged)), link));
+        }
+        if page.is_some() || page_size.is_some() {
+            let msg = "`page` and `page_size` can't be combined with `after` and `limit`";
+            return Err(Error::Validation(msg.to_string()).into());
+        }
+        let after = after.0.as_deref().map(Cursor::decode).transpose()?;
+        let params = KeysetParams::new(after, limit.0);
+        let articles = state
+            .service
+            .list_articles_after(&filter, params, viewed_by(&viewer))
+            .await?;
+        let (sliced, link) = Sliced::new(req.uri(), articles);
+        Ok(Listing::Ok(Json(ArticleListing::Slice(sliced)), link))
+    }
+
+    /// Search articles
+    ///
+    /// Words must all match; use `"quotes"` for phrases and a trailing `*` for
+    /// prefixes. Best matches come first.
+    #[oai(path = "/articles/search", method = "get")]
+    async fn search_articles(
+        &self,
+        state: Data<&AppStateM>,
+        req: &Request,
+        q: Query<String>,
+        page: Query<Option<i64>>,
+        page_size: Query<Option<i64>>,
+    ) -> ApiResult<Listing<Paged<SearchResult>>> {
+        let params = Params::new(page.0, page_size.0);
+        let hits = state.service.search_articles(&q.0, params).await?;
+        let (paged, link) = Paged::new(req.uri(), hits);
+        Ok(Listing::Ok(Json(paged), link))
+    }
+
+    /// Get an article with its author
+    ///
+    /// Unpublished articles are only found by those who may edit them.
+    #[oai(path = "/articles/:id", method = "get")]
+    async fn get_article_by_id(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+    ) -> ApiResult<Json<ArticleWithAuthor>> {
+        let found = state
+            .service
+            .get_article_with_author(id.0, viewed_by(&viewer))
+            .await?;
+        Ok(Json(found.into()))
+    }
+
+    /// Get an article by its slug
+    ///
+    /// Slugs the article had before a title change redirect permanently to
+    /// the current one.
+    #[oai(path = "/articles/by-slug/:slug", method = "get")]
+    async fn get_article_by_slug(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        slug: Path<String>,
+    ) -> ApiResult<BySlug> {
+        let viewer = viewed_by(&viewer);
+        let article = state.service.get_article_by_slug(&slug.0, viewer).await?;
+        if article.slug != slug.0 {
+            return Ok(BySlug::MovedPermanently(format!(
+                "/articles/by-slug/{}",
+                slug::to_path(&article.slug)
+            )));
+        }
+        let found = state
+            .service
+            .get_article_with_author(article.id, viewer)
+            .await?;
+        Ok(BySlug::Ok(Json(Box::new(found.into()))))
+    }
+
+    /// Replace all editable fields of an article
+    #[oai(path = "/articles/:id", method = "put")]
+    async fn replace_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        body: Json<ArticleReplace>,
+    ) -> ApiResult<Json<Article>> {
+        let update = ArticleUpdate {
+            title: Some(body.0.title),
+            content: Some(body.0.content),
+            editor_id: Some(author.0.id),
+        };
+        Ok(Json(
+            state
+                .service
+                .update_article(id.0, &update, &author.0)
+                .await?
+                .into(),
+        ))
+    }
+
+    /// Update only the fields that are sent
+    #[oai(path = "/articles/:id", method = "patch")]
+    async fn update_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        body: Json<ArticlePatch>,
+    ) -> ApiResult<Json<Article>> {
+        let update = ArticleUpdate {
+            title: body.0.title,
+            content: match body.0.content {
+                MaybeUndefined::Undefined => None,
+                MaybeUndefined::Null => Some(None),
+                MaybeUndefined::Value(content) => Some(Some(content)),
+            },
+            editor_id: Some(author.0.id),
+        };
+        Ok(Json(
+            state
+                .service
+                .update_article(id.0, &update, &author.0)
+                .await?
+                .into(),
+        ))
+    }
+
+    /// Delete an article
+    #[oai(path = "/articles/:id", method = "delete")]
+    async fn delete_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+    ) -> ApiResult<Deleted> {
+        state.service.delete_article(id.0, &author.0).await?;
+        Ok(Deleted::NoContent)
+    }
+
+    /// Move an article along the publishing workflow
+    #[oai(path = "/articles/:id/status", method = "put")]
+    async fn transition_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        body: Json<StatusChange>,
+    ) -> ApiResult<Json<Article>> {
+        let article = state
+            .service
+            .transition_article(id.0, body.0.status.into(), &author.0)
+            .await?;
+        Ok(Json(article.into()))
+    }
+
+    /// Schedule a draft or reviewed article to be published
+    #[oai(path = "/articles/:id/schedule", method = "put")]
+    async fn schedule_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        body: Json<Schedule>,
+    ) -> ApiResult<Json<Article>> {
+        let article = state
+            .service
+            .schedule_article(id.0, body.0.publish_at, &author.0)
+            .await?;
+        Ok(Json(article.into()))
+    }
+
+    /// List the revisions of an article, oldest first
+    #[oai(path = "/articles/:id/revisions", method = "get")]
+    async fn list_revisions(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+    ) -> ApiResult<Json<Vec<Revision>>> {
+        let revisions = state
+            .service
+            .list_revisions(id.0, viewed_by(&viewer))
+            .await?;
+        Ok(Json(revisions.into_iter().map(Into::into).collect()))
+    }
+
+    /// Get one revision of an article
+    #[oai(path = "/articles/:id/revisions/:rev", method = "get")]
+    async fn get_revision(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+        rev: Path<i32>,
+    ) -> ApiResult<Json<Revision>> {
+        let revision = state
+            .service
+            .get_revision(id.0, rev.0, viewed_by(&viewer))
+            .await?;
+        Ok(Json(revision.into()))
+    }
+
+    /// Diff two revisions of an article
+    #[oai(path = "/articles/:id/diff", method = "get")]
+    async fn diff_revisions(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+        from: Query<i32>,
+        to: Query<i32>,
+        #[oai(default = "default_diff_format")] format: Query<DiffFormat>,
+    ) -> ApiResult<Json<RevisionDiff>> {
+        let diff = state
+            .service
+            .diff_revisions(id.0, from.0, to.0, format.0.into(), viewed_by(&viewer))
+            .await?;
+        Ok(Json(RevisionDiff::new(from.0, to.0, diff)))
+    }
+
+    /// Restore the title and content of a revision, recorded as a new revision
+    #[oai(path = "/articles/:id/revisions/:rev/restore", method = "post")]
+    async fn restore_revision(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        rev: Path<i32>,
+    ) -> ApiResult<Json<Article>> {
+        let restored = state
+            .service
+            .restore_revision(id.0, rev.0, &author.0)
+            .await?;
+        Ok(Json(restored.into()))
+    }
+
+    /// List where an article was posted, per channel
+    #[oai(path = "/articles/:id/publications", method = "get")]
+    async fn list_publications(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+    ) -> ApiResult<Json<Vec<Publication>>> {
+        let publications = state
+            .publication_service
+            .list_publications(id.0, viewed_by(&viewer))
+            .await?;
+        Ok(Json(publications.into_iter().map(Into::into).collect()))
+    }
+
+    /// Post a published article to a channel again
+    ///
+    /// Answers with the publication either way; a failure shows in `state`
+    /// and `last_error`.
+    #[oai(path = "/articles/:id/publications/:channel", method = "post")]
+    async fn republish_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        channel: Path<String>,
+    ) -> ApiResult<Json<Publication>> {
+        let publication = state
+            .publication_service
+            .republish_article(id.0, &channel.0, &author.0)
+            .await?;
+        Ok(Json(publication.into()))
+    }
+}
+
+pub struct AuthorApi;
+
+#[OpenApi(tag = "ApiTags::Authors")]
+impl AuthorApi {
+    /// Create an author
+    ///
+    /// Needs an admin, except for the first author, who becomes the admin.
+    #[oai(path = "/authors", method = "post")]
+    async fn create_author(
+        &self,
+        state: Data<&AppStateM>,
+        admin: Option<CurrentAuthor>,
+        body: CreateAuthorRequest,
+    ) -> ApiResult<Created<Author>> {
+        let (CreateAuthorRequest::Json(Json(new)) | CreateAuthorRequest::Form(Form(new))) = body;
+        let msg = AuthorCreate {
+            first_name: new.first_name,
+            last_name: new.last_name,
+            email: new.email,
+            password: new.password,
+            role: new.role.map(Into::into).unwrap_or_default(),
+        };
+        let created = match admin {
+            Some(admin) => state.author_service.create_author(&msg, &admin.0).await?,
+            None => state.author_service.create_first_author(&msg).await?,
+        };
+        Ok(Created::Created(Json(author::Model::from(created).into())))
+    }
+
+    /// List a page of authors
+    ///
+    /// Pages start at 1; out of range values are clamped.
+    #[oai(path = "/authors", method = "get")]
+    async fn list_authors(
+        &self,
+        state: Data<&AppStateM>,
+        req: &Request,
+        page: Query<Option<i64>>,
+        page_size: Query<Option<i64>>,
+    ) -> ApiResult<Listing<Paged<Author>>> {
+        let params = Params::new(page.0, page_size.0);
+        let authors = state.author_service.list_authors(params).await?;
+        let (paged, link) = Paged::new(req.uri(), authors);
+        Ok(Listing::Ok(Json(paged), link))
+    }
+
+    /// Get an author
+    #[oai(path = "/authors/:id", method = "get")]
+    async fn get_author_by_id(
+        &self,
+        state: Data<&AppStateM>,
+        id: Path<i32>,
+    ) -> ApiResult<Json<Author>> {
+        Ok(Json(
+            state.author_service.get_author_by_id(id.0).await?.into(),
+        ))
+    }
+
+    /// List all articles of an author
+    #[oai(path = "/authors/:id/articles", method = "get")]
+    async fn list_author_articles(
+        &self,
+        state: Data<&AppStateM>,
+        id: Path<i32>,
+    ) -> ApiResult<Json<Vec<Article>>> {
+        let articles = state.service.list_articles_by_author(id.0).await?;
+        Ok(Json(articles.into_iter().map(Into::into).collect()))
+    }
+
+    /// Replace all fields of an author
+    #[oai(path = "/authors/:id", method = "put")]
+    async fn replace_author(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        body: Json<NewAuthor>,
+    ) -> ApiResult<Json<Author>> {
+        let update = AuthorUpdate {
+            first_name: Some(body.0.first_name),
+            last_name: Some(body.0.last_name),
+            email: Some(body.0.email),
+            password: body.0.password,
+            role: body.0.role.map(Into::into),
+        };
+        Ok(Json(
+            state
+                .author_service
+                .update_author(id.0, &update, &author.0)
+                .await?
+                .into(),
+        ))
+    }
+
+    /// Update only the fields that are sent
+    #[oai(path = "/authors/:id", method = "patch")]
+    async fn update_author(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        body: Json<AuthorPatch>,
+    ) -> ApiResult<Json<Author>> {
+        let update = AuthorUpdate {
+            first_name: body.0.first_name,
+            last_name: body.0.last_name,
+            email: body.0.email,
+            password: body.0.password,
+            role: body.0.role.map(Into::into),
+        };
+        Ok(Json(
+            state
+                .author_service
+                .update_author(id.0, &update, &author.0)
+                .await?
+                .into(),
+        ))
+    }
+
+    /// Delete an author
+    ///
+    /// Needs an admin, like giving roles. Others may only change their own
+    /// name, email and password.
+    #[oai(path = "/authors/:id", method = "delete")]
+    async fn delete_author(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+    ) -> ApiResult<Deleted> {
+        state.author_service.delete_author(id.0, &author.0).await?;
+        Ok(Deleted::NoContent)
+    }
+}
+
+pub struct TagApi;
+
+#[OpenApi(tag = "ApiTags::Tags")]
+impl TagApi {
+    /// List all tags with their article counts
+    #[oai(path = "/tags", method = "get")]
+    async fn list_tags(&self, state: Data<&AppStateM>) -> ApiResult<Json<Vec<TagCount>>> {
+        let tags = state.tag_service.list_tags().await?;
+        Ok(Json(tags.into_iter().map(Into::into).collect()))
+    }
+
+    /// List a page of the articles with a tag
+    #[oai(path = "/tags/:slug/articles", method = "get")]
+    async fn list_tag_articles(
+        &self,
+        state: Data<&AppStateM>,
+        req: &Request,
+        slug: Path<String>,
+        page: Query<Option<i64>>,
+        page_size: Query<Option<i64>>,
+    ) -> ApiResult<Listing<Paged<Article>>> {
+        let tag = state.tag_service.get_tag(&slug.0).await?;
+        let filter = ArticleFilter {
+            tag: Some(tag.slug),
+            ..ArticleFilter::published()
+        };
+        let params = Params::new(page.0, page_size.0);
+        let articles = state.service.list_articles(&filter, params, None).await?;
+        let (paged, link) = Paged::new(req.uri(), articles);
+        Ok(Listing::Ok(Json(paged), link))
+    }
+
+    /// List the tags of an article
+    #[oai(path = "/articles/:id/tags", method = "get")]
+    async fn list_article_tags(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+    ) -> ApiResult<Json<Vec<Tag>>> {
+        let tags = state
+            .tag_service
+            .list_article_tags(id.0, viewed_by(&viewer))
+            .await?;
+        Ok(Json(tags.into_iter().map(Into::into).collect()))
+    }
+
+    /// Tag an article, creating the tag on first use
+    #[oai(path = "/articles/:id/tags", method = "post")]
+    async fn tag_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        body: Json<NewTag>,
+    ) -> ApiResult<Created<Tag>> {
+        let tag = state
+            .tag_service
+            .tag_article(id.0, &body.0.name, &author.0)
+            .await?;
+        Ok(Created::Created(Json(tag.into())))
+    }
+
+    /// Remove a tag from an article
+    #[oai(path = "/articles/:id/tags/:slug", method = "delete")]
+    async fn untag_article(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        slug: Path<String>,
+    ) -> ApiResult<Deleted> {
+        state
+            .tag_service
+            .untag_article(id.0, &slug.0, &author.0)
+            .await?;
+        Ok(Deleted::NoContent)
+    }
+}
+
+pub struct CommentApi;
+
+#[OpenApi(tag = "ApiTags::Comments")]
+impl CommentApi {
+    /// List the comments of an article as threads
+    #[oai(path = "/articles/:id/comments", method = "get")]
+    async fn list_comments(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+    ) -> ApiResult<Json<Vec<CommentThread>>> {
+        let threads = state
+            .comment_service
+            .list_comments(id.0, viewed_by(&viewer))
+            .await?;
+        Ok(Json(threads.into_iter().map(Into::into).collect()))
+    }
+
+    /// Comment on an article or reply to a comment
+    #[oai(path = "/articles/:id/comments", method = "post")]
+    async fn post_comment(
+        &self,
+        state: Data<&AppStateM>,
+        viewer: Option<CurrentAuthor>,
+        id: Path<i32>,
+        body: Json<NewComment>,
+    ) -> ApiResult<Created<Comment>> {
+        let body = body.0;
+        let create = CommentCreate {
+            article_id: id.0,
+            parent_id: body.parent_id,
+            author_name: body.author_name,
+            body: body.body,
+        };
+        let created = state
+            .comment_service
+            .post_comment(&create, viewed_by(&viewer))
+            .await?;
+        Ok(Created::Created(Json(created.into())))
+    }
+
+    /// Edit a comment
+    ///
+    /// The article's author, editors and admins moderate its comments.
+    #[oai(path = "/articles/:id/comments/:comment_id", method = "put")]
+    async fn edit_comment(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        comment_id: Path<i32>,
+        body: Json<CommentEdit>,
+    ) -> ApiResult<Json<Comment>> {
+        let edited = state
+            .comment_service
+            .edit_comment(id.0, comment_id.0, &body.0.body, &author.0)
+            .await?;
+        Ok(Json(edited.into()))
+    }
+
+    /// Delete a comment and its replies
+    #[oai(path = "/articles/:id/comments/:comment_id", method = "delete")]
+    async fn delete_comment(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+        comment_id: Path<i32>,
+    ) -> ApiResult<Deleted> {
+        state
+            .comment_service
+            .delete_comment(id.0, comment_id.0, &author.0)
+            .await?;
+        Ok(Deleted::NoContent)
+    }
+}
+
+pub struct AuthApi;
+
+#[OpenApi(tag = "ApiTags::Auth")]
+impl AuthApi {
+    /// Log in with an email and password
+    ///
+    /// Starts a session, kept in a cookie, and returns the author.
+    #[oai(path = "/auth/login", method = "post")]
+    async fn login(
+        &self,
+        state: Data<&AppStateM>,
+        session: &Session,
+        body: Json<Credentials>,
+    ) -> ApiResult<Json<Author>> {
+        let author = state
+            .author_service
+            .authenticate(&body.0.email, &body.0.password)
+            .await?;
+        auth::log_in(session, &author);
+        Ok(Json(author.into()))
+    }
+
+    /// Log out
+    #[oai(path = "/auth/logout", method = "post")]
+    async fn logout(&self, session: &Session) -> LoggedOut {
+        auth::log_out(session);
+        LoggedOut::NoContent
+    }
+
+    /// Get the logged in author
+    #[oai(path = "/auth/me", method = "get")]
+    async fn me(&self, author: CurrentAuthor) -> Json<Author> {
+        Json(author.0.into())
+    }
+}
+
+pub struct TokenApi;
+
+#[OpenApi(tag = "ApiTags::Tokens")]
+impl TokenApi {
+    /// Mint an API token
+    ///
+    /// The response holds the secret, which is not shown again.
+    #[oai(path = "/tokens", method = "post")]
+    async fn mint_token(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        body: Json<NewToken>,
+    ) -> ApiResult<Created<MintedToken>> {
+        let scopes: Vec<api_token::Scope> = body.0.scopes.into_iter().map(Into::into).collect();
+        let (token, secret) = state
+            .token_service
+            .mint_token(author.0.id, &body.0.name, &scopes, body.0.expires_in_days)
+            .await?;
+        Ok(Created::Created(Json(MintedToken {
+            token: token.into(),
+            secret,
+        })))
+    }
+
+    /// List the tokens of the logged in author
+    #[oai(path = "/tokens", method = "get")]
+    async fn list_tokens(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+    ) -> ApiResult<Json<Vec<ApiToken>>> {
+        let tokens = state.token_service.list_tokens(author.0.id).await?;
+        Ok(Json(tokens.into_iter().map(Into::into).collect()))
+    }
+
+    /// Revoke a token
+    #[oai(path = "/tokens/:id", method = "delete")]
+    async fn revoke_token(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+    ) -> ApiResult<Deleted> {
+        state.token_service.revoke_token(author.0.id, id.0).await?;
+        Ok(Deleted::NoContent)
+    }
+}
+
+pub struct OutboxApi;
+
+#[OpenApi(tag = "ApiTags::Outbox")]
+impl OutboxApi {
+    /// List outbox entries, newest first
+    ///
+    /// Needs an admin. `state=dead` lists the entries that gave up.
+    #[oai(path = "/outbox", method = "get")]
+    async fn list_outbox(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        req: &Request,
+        page: Query<Option<i64>>,
+        page_size: Query<Option<i64>>,
+        #[oai(name = "state")] entry_state: Query<Option<OutboxState>>,
+    ) -> ApiResult<Listing<Paged<OutboxEntry>>> {
+        let params = Params::new(page.0, page_size.0);
+        let entries = state
+            .outbox_service
+            .list_entries(entry_state.0.map(Into::into), params, &author.0)
+            .await?;
+        let (paged, link) = Paged::new(req.uri(), entries);
+        Ok(Listing::Ok(Json(paged), link))
+    }
+
+    /// Replay an outbox entry
+    ///
+    /// Needs an admin. Queues a pending or dead entry for delivery right
+    /// away, with a fresh round of attempts.
+    #[oai(path = "/outbox/:id/replay", method = "post")]
+    async fn replay_outbox_entry(
+        &self,
+        state: Data<&AppStateM>,
+        author: CurrentAuthor,
+        id: Path<i32>,
+    ) -> ApiResult<Json<OutboxEntry>> {
+        let entry = state.outbox_service.replay_entry(id.0, &author.0).await?;
+        Ok(Json(entry.into()))
+    }
+}
+
+/// Top-level paths served by [`service`].
+pub const PREFIXES: &[&str] = &[
+    "/articles",
+    "/authors",
+    "/tags",
+    "/auth",
+    "/tokens",
+    "/outbox",
+];
+
+#[allow(clippy::type_complexity)]
+pub fn service() -> OpenApiService<
+    (
+        ArticleApi,
+        AuthorApi,
+        TagApi,
+        CommentApi,
+        AuthApi,
+        TokenApi,
+        OutboxApi,
+    ),
+    (),
+> {
+    OpenApiService::new(
+        (
+            ArticleApi, AuthorApi, TagApi, CommentApi, AuthApi, TokenApi, OutboxApi,
+        ),
+        "poem_article",
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use crate::diff::{self, Diff};
+    use crate::domain::{api_token, article, author, comment, outbox, publication, tag};
+    use crate::error::{Error, PROBLEM_JSON};
+    use crate::handlers::config_router;
+    use crate::pagination::{Cursor, KeysetParams, Page, Params, Slice};
+    use crate::repositories::{ArticleFilter, ArticleUpdate, AuthorUpdate};
+    use crate::services::tests::draft;
+    use crate::services::{
+        MockArticleServiceTrait, MockAuthorServiceTrait, MockCommentServiceTrait,
+        MockOutboxServiceTrait, MockPublicationServiceTrait, MockSocialMediaPublisherTrait,
+        MockTagServiceTrait, MockTokenServiceTrait,
+    };
+    use crate::session::{SessionConfig, COOKIE_NAME};
+    use crate::AppStateM;
+    use mockall::predicate::*;
+    use poem::test::{TestClient, TestResponse};
+    use poem::{http::StatusCode, Endpoint, Response};
+    use sea_orm::{ActiveEnum, Set};
+    use std::path::PathBuf;
+    use tera::Tera;
+
+    fn mock_state(mock: MockArticleServiceTrait, mock_author: MockAuthorServiceTrait) -> AppStateM {
+        AppStateM {
+            service: Arc::new(mock),
+            author_service: Arc::new(mock_author),
+            tag_service: Arc::new(MockTagServiceTrait::new()),
+            comment_service: Arc::new(MockCommentServiceTrait::new()),
+            token_service: Arc::new(MockTokenServiceTrait::new()),
+            outbox_service: Arc::new(MockOutboxServiceTrait::new()),
+            publication_service: Arc::new(MockPublicationServiceTrait::new()),
+            publisher: Arc::new(MockSocialMediaPublisherTrait::new()),
+            templates: Tera::default(),
+            resources: PathBuf::from("resources"),
+            session: SessionConfig::generate(),
+        }
+    }
+
+    fn get_client(
+        mock: MockArticleServiceTrait,
+        mock_author: MockAuthorServiceTrait,
+    ) -> TestClient<impl Endpoint<Output = Response>> {
+        TestClient::new(config_router(mock_state(mock, mock_author)))
+    }
+
+    pub fn ada(id: i32) -> author::Model {
+        author::Model {
+            id,
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            password_hash: None,
+            role: author::Role::Author,
+        }
+    }
+
+    /// Lets `ada@example.com` log in as author 3 with "correct horse".
+    pub fn with_login(mut mock_author: MockAuthorServiceTrait) -> MockAuthorServiceTrait {
+        mock_author
+            .expect_authenticate()
+            .returning(|email, password| match (email, password) {
+                ("ada@example.com", "correct horse") => Ok(ada(3)),
+                _ => Err(Error::Unauthorized),
+            });
+        mock_author
+            .expect_get_author_by_id()
+            .with(eq(3))
+            .returning(|id| Ok(ada(id)));
+        mock_author
+    }
+
+    /// The `name=value` part of the session cookie the response sets.
+    pub fn session_cookie(resp: &TestResponse) -> String {
+        let set_cookie = resp.0.headers()["Set-Cookie"].to_str().unwrap();
+        let cookie = set_cookie.split(';').next().unwrap();
+        assert!(cookie.starts_with(COOKIE_NAME));
+        cookie.to_string()
+    }
+
+    /// Logs in as in [`with_login`], returning the session cookie.
+    pub async fn log_in<E: Endpoint>(cli: &TestClient<E>) -> String {
+        let resp = cli
+            .post("/auth/login")
+            .body_json(&serde_json::json!({
+                "email": "ada@example.com",
+                "password": "correct horse",
+            }))
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        session_cookie(&resp)
+    }
+
+    #[tokio::test]
+    async fn get_article_by_id() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_article_with_author()
+            .withf(|id, viewer| *id == 1 && viewer.is_none())
+            .returning(|id, _viewer| {
+                Ok(article::WithAuthor {
+                    article: article::Model {
+                        status: article::Status::Published,
+                        ..draft(id, Some(3))
+                    },
+                    author: Some(ada(3)),
+                })
+            });
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli.get("/articles/1").send().await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let article = json.value().object();
+        article.get("title").assert_string("title");
+        article.get("author").object().get("id").assert_i64(3);
+    }
+
+    #[tokio::test]
+    async fn old_slug_redirects() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_article_by_slug()
+            .withf(|slug, _viewer| slug == "old-title")
+            .returning(|_, _| {
+                Ok(article::Model {
+                    title: "New title".to_string(),
+                    slug: "new-title".to_string(),
+                    ..draft(1, None)
+                })
+            });
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli.get("/articles/by-slug/old-title").send().await;
+        resp.assert_status(StatusCode::MOVED_PERMANENTLY);
+        resp.assert_header("Location", "/articles/by-slug/new-title");
+    }
+
+    #[tokio::test]
+    async fn list_articles_page() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_list_articles()
+            .with(
+                eq(ArticleFilter {
+                    tag: Some("rust".to_string()),
+                    ..ArticleFilter::published()
+                }),
+                eq(Params::new(Some(2), Some(100))),
+                always(),
+            )
+            .returning(|_filter, params, _viewer| {
+                let items = vec![article::Model {
+                    status: article::Status::Published,
+                    ..draft(101, None)
+                }];
+                Ok(Page::new(items, params, 250))
+            });
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli
+            .get("/articles")
+            .query("tag", &"rust")
+            .query("page", &2)
+            .query("page_size", &1000)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header(
+            "Link",
+            "</articles?tag=rust&page=1&page_size=100>; rel=\"first\", \
+             </articles?tag=rust&page=1&page_size=100>; rel=\"prev\", \
+             </articles?tag=rust&page=3&page_size=100>; rel=\"next\", \
+             </articles?tag=rust&page=3&page_size=100>; rel=\"last\"",
+        );
+        let json = resp.json().await;
+        let page = json.value().object();
+        page.get("items").array().assert_len(1);
+        page.get("total_items").assert_i64(250);
+        page.get("total_pages").assert_i64(3);
+        page.get("next")
+            .assert_string("/articles?tag=rust&page=3&page_size=100");
+    }
+
+    #[tokio::test]
+    async fn unpublished_listings_are_asked_for_by_the_viewer() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_list_articles()
+            .withf(|filter, _params, viewer| {
+                filter.status == Some(article::Status::Draft) && viewer.is_none()
+            })
+            .returning(|_filter, _params, _viewer| Err(Error::Unauthorized));
+        mock.expect_list_articles()
+            .withf(|filter, _params, viewer| {
+                filter.status == Some(article::Status::Draft) && viewer.is_some_and(|v| v.id == 3)
+            })
+            .returning(|_filter, params, _viewer| Ok(Page::new(vec![], params, 0)));
+        let cli = get_client(mock, with_login(MockAuthorServiceTrait::new()));
+
+        let resp = cli.get("/articles").query("status", &"draft").send().await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .get("/articles")
+            .query("status", &"draft")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+    }
+
+    #[tokio::test]
+    async fn list_articles_by_cursor() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_list_articles_after()
+            .with(
+                always(),
+                eq(KeysetParams::new(Some(Cursor(4)), Some(2))),
+                always(),
+            )
+            .returning(|_filter, params, _viewer| {
+                let items = (5..8)
+                    .map(|id| article::Model {
+                        status: article::Status::Published,
+                        ..draft(id, None)
+                    })
+                    .collect();
+                Ok(Slice::new(items, params, |article| article.id))
+            });
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli
+            .get("/articles")
+            .query("after", &Cursor(4).encode())
+            .query("limit", &2)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        let next = format!("/articles?after={}&limit=2", Cursor(6).encode());
+        resp.assert_header(
+            "Link",
+            format!("</articles?limit=2>; rel=\"first\", <{next}>; rel=\"next\""),
+        );
+        let json = resp.json().await;
+        let slice = json.value().object();
+        slice.get("items").array().assert_len(2);
+        slice.get("next_cursor").assert_string(&Cursor(6).encode());
+        slice.get("next").assert_string(&next);
+        assert!(slice.get_opt("total_items").is_none());
+    }
+
+    #[tokio::test]
+    async fn list_articles_rejects_bad_cursors() {
+        let cli = get_client(
+            MockArticleServiceTrait::new(),
+            MockAuthorServiceTrait::new(),
+        );
+        let resp = cli.get("/articles").query("after", &"%%%").send().await;
+        resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+        let resp = cli
+            .get("/articles")
+            .query("page", &2)
+            .query("limit", &5)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn search_articles() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_search_articles()
+            .with(eq(r#""sea orm" migr*"#), eq(Params::default()))
+            .returning(|_q, params| {
+                let hit = article::SearchHit {
+                    article: article::Model {
+                        title: "Migrations".to_string(),
+                        slug: "migrations".to_string(),
+                        status: article::Status::Published,
+                        ..draft(4, None)
+                    },
+                    score: 1.5,
+                    snippet: "<mark>Migrations</mark>".to_string(),
+                };
+                Ok(Page::new(vec![hit], params, 1))
+            });
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli
+            .get("/articles/search")
+            .query("q", &r#""sea orm" migr*"#)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let hit = json.value().object().get("items").array().get(0).object();
+        hit.get("id").assert_i64(4);
+        hit.get("snippet").assert_string("<mark>Migrations</mark>");
+    }
+
+    #[tokio::test]
+    async fn get_missing_article() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_article_with_author()
+            .returning(|_id, _viewer| Err(Error::NotFound("article")));
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli.get("/articles/7").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_content_type(PROBLEM_JSON);
+        let json = resp.json().await;
+        let problem = json.value().object();
+        problem.get("status").assert_i64(404);
+        problem.get("detail").assert_string("article not found");
+    }
+
+    #[tokio::test]
+    async fn internal_error_hides_details() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_article_with_author()
+            .returning(|_id, _viewer| Err(Error::Internal(anyhow::anyhow!("database is locked"))));
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli.get("/articles/7").send().await;
+        resp.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        resp.assert_content_type(PROBLEM_JSON);
+        let json = resp.json().await;
+        let problem = json.value().object();
+        problem.get("title").assert_string("Internal Server Error");
+        assert!(problem.get_opt("detail").is_none());
+    }
+
+    #[tokio::test]
+    async fn validation_error() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_create_article()
+            .returning(|_title, _content, _author_id, _actor| {
+                Err(Error::Validation("title must not be empty".to_string()))
+            });
+
+        let cli = get_client(mock, with_login(MockAuthorServiceTrait::new()));
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .post("/articles")
+            .header("Cookie", cookie)
+            .body_json(&serde_json::json!({ "title": "" }))
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        resp.assert_content_type(PROBLEM_JSON);
+    }
+
+    #[tokio::test]
+    async fn unknown_route_is_a_problem() {
+        let cli = get_client(
+            MockArticleServiceTrait::new(),
+            MockAuthorServiceTrait::new(),
+        );
+        let resp = cli.get("/articles/not-a-number").send().await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+        resp.assert_content_type(PROBLEM_JSON);
+
+        let resp = cli.get("/nowhere").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_content_type(PROBLEM_JSON);
+    }
+
+    #[tokio::test]
+    async fn patch_article_sends_only_present_fields() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_update_article()
+            .with(
+                eq(1),
+                eq(ArticleUpdate {
+                    title: None,
+                    content: Some(None),
+                    editor_id: Some(3),
+                }),
+                eq(ada(3)),
+            )
+            .returning(|id, _update, _actor| {
+                Ok(article::Model {
+                    status: article::Status::Published,
+                    ..draft(id, None)
+                })
+            });
+
+        let cli = get_client(mock, with_login(MockAuthorServiceTrait::new()));
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .patch("/articles/1")
+            .header("Cookie", cookie)
+            .body_json(&serde_json::json!({ "content": null }))
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+    }
+
+    #[tokio::test]
+    async fn delete_article() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_delete_article()
+            .returning(|id, _actor| match id {
+                1 => Ok(()),
+                _ => Err(Error::NotFound("article")),
+            });
+
+        let cli = get_client(mock, with_login(MockAuthorServiceTrait::new()));
+        let cookie = log_in(&cli).await;
+        cli.delete("/articles/1")
+            .header("Cookie", &cookie)
+            .send()
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        cli.delete("/articles/2")
+            .header("Cookie", &cookie)
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn changes_need_a_login() {
+        let cli = get_client(
+            MockArticleServiceTrait::new(),
+            with_login(MockAuthorServiceTrait::new()),
+        );
+        let resp = cli
+            .post("/articles")
+            .body_json(&serde_json::json!({ "title": "anonymous" }))
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+        resp.assert_content_type(PROBLEM_JSON);
+
+        let resp = cli
+            .post("/auth/login")
+            .body_json(&serde_json::json!({
+                "email": "ada@example.com",
+                "password": "battery staple",
+            }))
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+        assert!(resp.0.headers().get("Set-Cookie").is_none());
+
+        let cookie = log_in(&cli).await;
+        let resp = cli.get("/auth/me").header("Cookie", &cookie).send().await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        json.value().object().get("id").assert_i64(3);
+        assert!(json.value().object().get_opt("password_hash").is_none());
+
+        let resp = cli
+            .post("/auth/logout")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NO_CONTENT);
+        let resp = cli.get("/auth/me").header("Cookie", &cookie).send().await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    fn token(scopes: &str) -> api_token::Model {
+        api_token::Model {
+            id: 7,
+            author_id: 3,
+            name: "ci".to_string(),
+            token_hash: "hash".to_string(),
+            scopes: scopes.to_string(),
+            created_at: "2026-10-18T12:00:00Z".parse().unwrap(),
+            expires_at: "2026-11-17T12:00:00Z".parse().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn tokens_are_scoped() {
+        let mut mock_token = MockTokenServiceTrait::new();
+        mock_token
+            .expect_mint_token()
+            .withf(|author_id, name, scopes, days| {
+                *author_id == 3
+                    && name == "ci"
+                    && scopes == [api_token::Scope::ArticlesRead]
+                    && *days == 30
+            })
+            .returning(|_, _, _, _| Ok((token("articles:read"), "pa_read".to_string())));
+        mock_token
+            .expect_authenticate_token()
+            .returning(|secret| match secret {
+                "pa_read" => Ok((ada(3), token("articles:read"))),
+                _ => Err(Error::Unauthorized),
+            });
+        let state = AppStateM {
+            token_service: Arc::new(mock_token),
+            ..mock_state(
+                MockArticleServiceTrait::new(),
+                with_login(MockAuthorServiceTrait::new()),
+            )
+        };
+        let cli = TestClient::new(config_router(state));
+
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .post("/tokens")
+            .header("Cookie", &cookie)
+            .body_json(&serde_json::json!({ "name": "ci", "scopes": ["articles:read"] }))
+            .send()
+            .await;
+        resp.assert_status(StatusCode::CREATED);
+        let json = resp.json().await;
+        let minted = json.value().object();
+        minted.get("secret").assert_string("pa_read");
+        minted.get("id").assert_i64(7);
+        minted.get("scopes").assert_string_array(&["articles:read"]);
+        assert!(minted.get_opt("token_hash").is_none());
+
+        let resp = cli
+            .get("/auth/me")
+            .header("Authorization", "Bearer pa_read")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.json().await.value().object().get("id").assert_i64(3);
+
+        for (method, path, secret, status) in [
+            ("POST", "/articles", "pa_read", StatusCode::FORBIDDEN),
+            ("GET", "/tokens", "pa_read", StatusCode::FORBIDDEN),
+            ("GET", "/auth/me", "pa_revoked", StatusCode::UNAUTHORIZED),
+        ] {
+            let resp = cli
+                .request(method.parse().unwrap(), path)
+                .header("Authorization", format!("Bearer {secret}"))
+                .body_json(&serde_json::json!({ "title": "by a bot" }))
+                .send()
+                .await;
+            resp.assert_status(status);
+            resp.assert_content_type(PROBLEM_JSON);
+        }
+    }
+
+    fn dead_entry(id: i32) -> outbox::Model {
+        let at = "2026-10-18T12:00:00Z".parse().unwrap();
+        outbox::Model {
+            id,
+            event: outbox::Event::ArticlePublished,
+            article_id: 1,
+            state: outbox::State::Dead,
+            attempts: 10,
+            next_attempt_at: at,
+            last_error: Some("publishing to `hook` failed".to_string()),
+            created_at: at,
+            delivered_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn outbox_is_replayed() {
+        let mut mock_outbox = MockOutboxServiceTrait::new();
+        mock_outbox
+            .expect_list_entries()
+            .withf(|state, params, actor| {
+                *state == Some(outbox::State::Dead)
+                    && *params == Params::new(None, None)
+                    && actor.id == 3
+            })
+            .returning(|_, params, _| Ok(Page::new(vec![dead_entry(5)], params, 1)));
+        mock_outbox
+            .expect_replay_entry()
+            .returning(|id, _actor| match id {
+                5 => Ok(outbox::Model {
+                    state: outbox::State::Pending,
+                    attempts: 0,
+                    ..dead_entry(id)
+                }),
+                _ => Err(Error::Conflict(
+                    "a delivered entry can't be replayed".to_string(),
+                )),
+            });
+        let state = AppStateM {
+            outbox_service: Arc::new(mock_outbox),
+            ..mock_state(
+                MockArticleServiceTrait::new(),
+                with_login(MockAuthorServiceTrait::new()),
+            )
+        };
+        let cli = TestClient::new(config_router(state));
+        let cookie = log_in(&cli).await;
+
+        let resp = cli
+            .get("/outbox")
+            .query("state", &"dead")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let entry = json.value().object().get("items").array().get(0).object();
+        entry.get("event").assert_string("article.published");
+        entry.get("state").assert_string("dead");
+        entry
+            .get("last_error")
+            .assert_string("publishing to `hook` failed");
+
+        let resp = cli
+            .post("/outbox/5/replay")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.json()
+            .await
+            .value()
+            .object()
+            .get("state")
+            .assert_string("pending");
+        let resp = cli
+            .post("/outbox/6/replay")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::CONFLICT);
+    }
+
+    fn failed_publication(channel: &str) -> publication::Model {
+        let at = "2026-10-18T12:00:00Z".parse().unwrap();
+        publication::Model {
+            id: 1,
+            article_id: 1,
+            channel: channel.to_string(),
+            state: publication::State::Failed,
+            remote_id: None,
+            remote_url: None,
+            attempts: 3,
+            last_error: Some(format!("publishing to `{channel}` failed")),
+            posted_at: None,
+            updated_at: at,
+        }
+    }
+
+    #[tokio::test]
+    async fn publications_are_listed_and_republished() {
+        let mut mock_publication = MockPublicationServiceTrait::new();
+        mock_publication
+            .expect_list_publications()
+            .returning(|id, _viewer| match id {
+                1 => Ok(vec![failed_publication("social")]),
+                _ => Err(Error::NotFound("article")),
+            });
+        mock_publication
+            .expect_republish_article()
+            .withf(|id, channel, actor| *id == 1 && actor.id == 3 && channel != "fax")
+            .times(1)
+            .returning(|_, channel, _| {
+                Ok(publication::Model {
+                    state: publication::State::Posted,
+                    remote_id: Some("1090".to_string()),
+                    remote_url: Some("https://social.example/@blog/1090".to_string()),
+                    attempts: 4,
+                    posted_at: Some("2026-10-18T12:05:00Z".parse().unwrap()),
+                    ..failed_publication(channel)
+                })
+            });
+        mock_publication
+            .expect_republish_article()
+            .returning(|_, _, _| Err(Error::NotFound("channel")));
+        let state = AppStateM {
+            publication_service: Arc::new(mock_publication),
+            ..mock_state(
+                MockArticleServiceTrait::new(),
+                with_login(MockAuthorServiceTrait::new()),
+            )
+        };
+        let cli = TestClient::new(config_router(state));
+
+        let resp = cli.get("/articles/1/publications").send().await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let publication = json.value().array().get(0).object();
+        publication.get("channel").assert_string("social");
+        publication.get("state").assert_string("failed");
+        publication.get("attempts").assert_i64(3);
+        publication
+            .get("last_error")
+            .assert_string("publishing to `social` failed");
+        let resp = cli.get("/articles/2/publications").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+
+        let resp = cli.post("/articles/1/publications/social").send().await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .post("/articles/1/publications/social")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let publication = json.value().object();
+        publication.get("state").assert_string("posted");
+        publication.get("remote_id").assert_string("1090");
+        publication
+            .get("remote_url")
+            .assert_string("https://social.example/@blog/1090");
+        let resp = cli
+            .post("/articles/1/publications/fax")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn diff_revisions() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_diff_revisions()
+            .with(eq(1), eq(1), eq(3), eq(diff::Format::Words), always())
+            .returning(|_id, _from, _to, _format, _viewer| {
+                Ok(Diff::Words(vec![
+                    diff::Change {
+                        op: diff::Op::Equal,
+                        text: "Hello ".to_string(),
+                    },
+                    diff::Change {
+                        op: diff::Op::Insert,
+                        text: "world".to_string(),
+                    },
+                ]))
+            });
+        mock.expect_diff_revisions()
+            .with(eq(1), eq(1), eq(2), eq(diff::Format::Unified), always())
+            .returning(|_id, _from, _to, _format, _viewer| Err(Error::NotFound("revision")));
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli
+            .get("/articles/1/diff")
+            .query("from", &1)
+            .query("to", &3)
+            .query("format", &"words")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let diff = json.value().object();
+        diff.get("to").assert_i64(3);
+        assert!(diff.get_opt("unified").is_none());
+        let changes = diff.get("changes").array();
+        changes.assert_len(2);
+        changes.get(1).object().get("op").assert_string("insert");
+
+        let resp = cli
+            .get("/articles/1/diff")
+            .query("from", &1)
+            .query("to", &2)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn patch_author() {
+        let mut mock_author = MockAuthorServiceTrait::new();
+        mock_author
+            .expect_update_author()
+            .with(
+                eq(3),
+                eq(AuthorUpdate {
+                    email: Some("ada@example.com".to_string()),
+                    ..Default::default()
+                }),
+                eq(ada(3)),
+            )
+            .returning(|id, _update, _actor| Ok(ada(id)));
+
+        let cli = get_client(MockArticleServiceTrait::new(), with_login(mock_author));
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .patch("/authors/3")
+            .header("Cookie", cookie)
+            .body_json(&serde_json::json!({ "email": "ada@example.com" }))
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+    }
+
+    #[tokio::test]
+    async fn authors_are_managed_by_admins() {
+        let mut mock_author = MockAuthorServiceTrait::new();
+        mock_author
+            .expect_create_first_author()
+            .withf(|new| new.email == "first@example.com")
+            .returning(|_new| Err(Error::Forbidden("only admins create authors".to_string())));
+        mock_author
+            .expect_create_author()
+            .withf(|new, actor| new.role == author::Role::Editor && actor.id == 3)
+            .returning(|_new, actor| {
+                Err(Error::Forbidden(format!(
+                    "the `{}` role may not manage authors",
+                    actor.role.to_value()
+                )))
+            });
+        let cli = get_client(MockArticleServiceTrait::new(), with_login(mock_author));
+        let new = serde_json::json!({
+            "first_name": "Grace",
+            "last_name": "Hopper",
+            "email": "first@example.com",
+            "password": "correct horse",
+            "role": "editor",
+        });
+
+        let resp = cli.post("/authors").body_json(&new).send().await;
+        resp.assert_status(StatusCode::FORBIDDEN);
+
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .post("/authors")
+            .header("Cookie", cookie)
+            .body_json(&new)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::FORBIDDEN);
+        resp.assert_content_type(PROBLEM_JSON);
+        resp.json()
+            .await
+            .value()
+            .object()
+            .get("detail")
+            .assert_string("the `author` role may not manage authors");
+    }
+
+    #[tokio::test]
+    async fn get_missing_author() {
+        let mut mock_author = MockAuthorServiceTrait::new();
+        mock_author
+            .expect_get_author_by_id()
+            .returning(|_id| Err(Error::NotFound("author")));
+
+        let cli = get_client(MockArticleServiceTrait::new(), mock_author);
+        let resp = cli.get("/authors/9").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn list_articles_of_missing_author() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_list_articles_by_author()
+            .with(eq(5))
+            .returning(|_author_id| Err(Error::NotFound("author")));
+
+        let cli = get_client(mock, MockAuthorServiceTrait::new());
+        let resp = cli.get("/authors/5/articles").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn create_article_from_form() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_create_article()
+            .with(
+                eq("hello"),
+                eq(Some("*hi*".to_string())),
+                eq(Some(3)),
+                eq(ada(3)),
+            )
+            .returning(|title, content, author_id, _actor| {
+                Ok(article::ActiveModel {
+                    id: Set(1),
+                    title: Set(title.to_string()),
+                    slug: Set(title.to_string()),
+                    content: Set(content),
+                    content_html: Set(Some("<p><em>hi</em></p>\n".to_string())),
+                    author_id: Set(author_id),
+                    status: Set(article::Status::Draft),
+                    published_at: Set(None),
+                    publish_at: Set(None),
+                })
+            });
+
+        let cli = get_client(mock, with_login(MockAuthorServiceTrait::new()));
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .post("/articles")
+            .header("Cookie", cookie)
+            .form(&[("title", "hello"), ("content", "*hi*")])
+            .send()
+            .await;
+        resp.assert_status(StatusCode::CREATED);
+        resp.json().await.value().object().get("id").assert_i64(1);
+    }
+
+    #[tokio::test]
+    async fn list_tag_articles() {
+        let mut mock_tag = MockTagServiceTrait::new();
+        mock_tag.expect_get_tag().returning(|name| match name {
+            "Rust" => Ok(tag::Model {
+                id: 1,
+                slug: "rust".to_string(),
+                name: "Rust".to_string(),
+            }),
+            _ => Err(Error::NotFound("tag")),
+        });
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_list_articles()
+            .with(
+                eq(ArticleFilter {
+                    tag: Some("rust".to_string()),
+                    ..ArticleFilter::published()
+                }),
+                always(),
+                always(),
+            )
+            .returning(|_filter, params, _viewer| Ok(Page::new(vec![], params, 0)));
+
+        let state = AppStateM {
+            tag_service: Arc::new(mock_tag),
+            ..mock_state(mock, MockAuthorServiceTrait::new())
+        };
+        let cli = TestClient::new(config_router(state));
+        let resp = cli.get("/tags/Rust/articles").send().await;
+        resp.assert_status_is_ok();
+        resp.json()
+            .await
+            .value()
+            .object()
+            .get("total_items")
+            .assert_i64(0);
+
+        let resp = cli.get("/tags/go/articles").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn tag_article() {
+        let mut mock_tag = MockTagServiceTrait::new();
+        mock_tag
+            .expect_tag_article()
+            .with(eq(7), eq("Web Dev"), eq(ada(3)))
+            .returning(|_id, name, _actor| {
+                Ok(tag::Model {
+                    id: 2,
+                    slug: "web-dev".to_string(),
+                    name: name.to_string(),
+                })
+            });
+
+        let state = AppStateM {
+            tag_service: Arc::new(mock_tag),
+            ..mock_state(
+                MockArticleServiceTrait::new(),
+                with_login(MockAuthorServiceTrait::new()),
+            )
+        };
+        let cli = TestClient::new(config_router(state));
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .post("/articles/7/tags")
+            .header("Cookie", cookie)
+            .body_json(&serde_json::json!({ "name": "Web Dev" }))
+            .send()
+            .await;
+        resp.assert_status(StatusCode::CREATED);
+        resp.json()
+            .await
+            .value()
+            .object()
+            .get("slug")
+            .assert_string("web-dev");
+    }
+
+    fn comment(id: i32, parent_id: Option<i32>) -> comment::Model {
+        comment::Model {
+            id,
+            article_id: 1,
+            parent_id,
+            author_name: "Ada".to_string(),
+            body: format!("comment {id}"),
+            created_at: "2026-10-18T12:00:00Z".parse().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn list_comment_threads() {
+        let mut mock_comment = MockCommentServiceTrait::new();
+        mock_comment
+            .expect_list_comments()
+            .withf(|id, viewer| *id == 1 && viewer.is_none())
+            .returning(|_id, _viewer| {
+                Ok(comment::Thread::build(vec![
+                    comment(1, None),
+                    comment(2, Some(1)),
+                    comment(3, None),
+                ]))
+            });
+        let state = AppStateM {
+            comment_service: Arc::new(mock_comment),
+            ..mock_state(
+                MockArticleServiceTrait::new(),
+                MockAuthorServiceTrait::new(),
+            )
+        };
+        let cli = TestClient::new(config_router(state));
+
+        let resp = cli.get("/articles/1/comments").send().await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let threads = json.value().array();
+        threads.assert_len(2);
+        let first = threads.get(0).object();
+        first
+            .get("created_at")
+            .assert_string("2026-10-18T12:00:00+00:00");
+        let replies = first.get("replies").array();
+        replies.assert_len(1);
+        replies.get(0).object().get("parent_id").assert_i64(1);
+    }
+
+    #[tokio::test]
+    async fn reply_to_comment_on_other_article() {
+        let mut mock_comment = MockCommentServiceTrait::new();
+        mock_comment
+            .expect_post_comment()
+            .withf(|c, _viewer| c.article_id == 1 && c.parent_id == Some(9))
+            .returning(|_c, _viewer| Err(Error::Validation("not on this article".to_string())));
+        let state = AppStateM {
+            comment_service: Arc::new(mock_comment),
+            ..mock_state(
+                MockArticleServiceTrait::new(),
+                MockAuthorServiceTrait::new(),
+            )
+        };
+        let cli = TestClient::new(config_router(state));
+
+        let resp = cli
+            .post("/articles/1/comments")
+            .body_json(&serde_json::json!({
+                "author_name": "Ada",
+                "body": "Agreed",
+                "parent_id": 9
+            }))
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn moderating_comments_needs_a_login() {
+        let mut mock_comment = MockCommentServiceTrait::new();
+        mock_comment
+            .expect_edit_comment()
+            .withf(|article_id, id, body, actor| {
+                *article_id == 1 && *id == 2 && body == "Edited" && actor.id == 3
+            })
+            .times(1)
+            .returning(|_, id, body, _| {
+                Ok(comment::Model {
+                    body: body.to_string(),
+                    ..comment(id, None)
+                })
+            });
+        mock_comment
+            .expect_delete_comment()
+            .withf(|article_id, id, actor| *article_id == 1 && *id == 2 && actor.id == 3)
+            .times(1)
+            .returning(|_, _, _| Err(Error::Forbidden("not yours".to_string())));
+        let state = AppStateM {
+            comment_service: Arc::new(mock_comment),
+            ..mock_state(
+                MockArticleServiceTrait::new(),
+                with_login(MockAuthorServiceTrait::new()),
+            )
+        };
+        let cli = TestClient::new(config_router(state));
+        let edit = serde_json::json!({ "body": "Edited" });
+
+        let resp = cli
+            .put("/articles/1/comments/2")
+            .body_json(&edit)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+        let resp = cli.delete("/articles/1/comments/2").send().await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .put("/articles/1/comments/2")
+            .header("Cookie", &cookie)
+            .body_json(&edit)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.json()
+            .await
+            .value()
+            .object()
+            .get("body")
+            .assert_string("Edited");
+        let resp = cli
+            .delete("/articles/1/comments/2")
+            .header("Cookie", &cookie)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn openapi_spec() {
+        let cli = get_client(
+            MockArticleServiceTrait::new(),
+            MockAuthorServiceTrait::new(),
+        );
+        let resp = cli.get("/api/openapi.json").send().await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let paths = json.value().object().get("paths").object();
+        paths.get("/articles/{id}").object().get("patch").object();
+        paths
+            .get("/authors/{id}/articles")
+            .object()
+            .get("get")
+            .object();
+
+        cli.get("/api/docs").send().await.assert_status_is_ok();
+    }
+}
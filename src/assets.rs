@@ -0,0 +1,54 @@
+//! Templates and static files. They are read from the resources directory
+//! (`RESOURCES_DIR`, `resources` by default), which holds `templates/` and
+//! `static/`, or are compiled into the binary with the `embed` feature.
+
+use poem::http::header;
+use poem::middleware::SetHeader;
+use poem::{Endpoint, EndpointExt, Response};
+use std::path::Path;
+use tera::Tera;
+
+/// Browsers may reuse a static file for an hour, then revalidate it with
+/// its ETag.
+pub const CACHE_CONTROL: &str = "public, max-age=3600";
+
+#[cfg(feature = "embed")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "resources/templates"]
+struct EmbeddedTemplates;
+
+#[cfg(feature = "embed")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "resources/static"]
+struct EmbeddedStatic;
+
+#[cfg(not(feature = "embed"))]
+pub fn templates(resources: &Path) -> tera::Result<Tera> {
+    Tera::new(&format!("{}/templates/**/*", resources.display()))
+}
+
+#[cfg(feature = "embed")]
+pub fn templates(_resources: &Path) -> tera::Result<Tera> {
+    let files = EmbeddedTemplates::iter().filter_map(|name| {
+        let file = EmbeddedTemplates::get(&name)?;
+        Some((
+            name.into_owned(),
+            String::from_utf8_lossy(&file.data).into_owned(),
+        ))
+    });
+    let mut tera = Tera::default();
+    tera.add_raw_templates(files.collect::<Vec<_>>())?;
+    Ok(tera)
+}
+
+/// Serves `static/`, to be nested under `/static`.
+pub fn static_files(resources: &Path) -> impl Endpoint<Output = Response> {
+    #[cfg(not(feature = "embed"))]
+    let files = poem::endpoint::StaticFilesEndpoint::new(resources.join("static"));
+    #[cfg(feature = "embed")]
+    let files = {
+        let _ = resources;
+        poem::endpoint::EmbeddedFilesEndpoint::<EmbeddedStatic>::new()
+    };
+    files.with(SetHeader::new().overriding(header::CACHE_CONTROL, CACHE_CONTROL))
+}
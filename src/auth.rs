@@ -0,0 +1,198 @@
+//! Password logins and API tokens. Passwords are stored as Argon2 hashes,
+//! and the logged in author is kept in the session and resolved for each
+//! request. Machine clients send a bearer token instead.
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use poem::http::{header, Method};
+use poem::session::Session;
+use poem::{Endpoint, FromRequest, IntoResponse, Request, RequestBody, Response};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::domain::api_token::Scope;
+use crate::domain::author;
+use crate::error::{Error, Result};
+use crate::AppStateM;
+
+/// The session entry holding the id of the logged in author.
+const SESSION_AUTHOR: &str = "author_id";
+
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Makes tokens easy to recognize, e.g. by secret scanners.
+const TOKEN_PREFIX: &str = "pa_";
+
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| Error::Internal(anyhow::anyhow!("hashing a password: {e}")))
+}
+
+/// `false` for a malformed hash too.
+pub fn verify_password(hash: &str, password: &str) -> bool {
+    PasswordHash::new(hash)
+        .and_then(|hash| Argon2::default().verify_password(password.as_bytes(), &hash))
+        .is_ok()
+}
+
+/// A new random token secret.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{TOKEN_PREFIX}{}", hex::encode(bytes))
+}
+
+/// Tokens are random, so a fast hash is enough and lets them be looked up.
+pub fn hash_token(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// The scope a token needs for a request: reading needs `articles:read`,
+/// changing articles, their tags and comments `articles:write`, and
+/// everything else, like managing authors and tokens, `admin`.
+pub fn required_scope(method: &Method, path: &str) -> Scope {
+    let reading = matches!(*method, Method::GET | Method::HEAD);
+    let under = |prefix: &str| path == prefix || path.starts_with(&format!("{prefix}/"));
+    if under("/tokens") || under("/outbox") {
+        return Scope::Admin;
+    }
+    match (reading, under("/articles")) {
+        (true, _) => Scope::ArticlesRead,
+        (false, true) => Scope::ArticlesWrite,
+        (false, false) => Scope::Admin,
+    }
+}
+
+/// Starts a new session for `author`, keeping its other entries.
+pub fn log_in(session: &Session, author: &author::Model) {
+    session.renew();
+    session.set(SESSION_AUTHOR, author.id);
+}
+
+pub fn log_out(session: &Session) {
+    session.renew();
+    session.remove(SESSION_AUTHOR);
+}
+
+/// The logged in author. As an extractor it rejects anonymous requests with
+/// `401`; take an `Option<CurrentAuthor>` where logging in is optional.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentAuthor(pub author::Model);
+
+#[poem::async_trait]
+impl<'a> FromRequest<'a> for CurrentAuthor {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
+        req.extensions()
+            .get::<CurrentAuthor>()
+            .cloned()
+            .ok_or_else(|| Error::Unauthorized.into())
+    }
+}
+
+/// The author of a request where logging in is optional.
+pub fn viewed_by(viewer: &Option<CurrentAuthor>) -> Option<&author::Model> {
+    viewer.as_ref().map(|CurrentAuthor(author)| author)
+}
+
+/// Middleware resolving the author of the session into a [`CurrentAuthor`].
+/// Needs the session middleware and [`AppStateM`] as data around it.
+pub async fn resolve_author<E: Endpoint>(next: E, mut req: Request) -> poem::Result<Response> {
+    if req.extensions().get::<CurrentAuthor>().is_some() {
+        // Already authenticated with a token.
+        return Ok(next.call(req).await?.into_response());
+    }
+    let author_id = req
+        .extensions()
+        .get::<Session>()
+        .and_then(|session| session.get::<i32>(SESSION_AUTHOR));
+    if let Some(author_id) = author_id {
+        let state = req.data::<AppStateM>().expect("AppStateM is registered");
+        match state.author_service.get_author_by_id(author_id).await {
+            Ok(author) => {
+                req.extensions_mut().insert(CurrentAuthor(author));
+            }
+            // The author was deleted since logging in.
+            Err(Error::NotFound(_)) => {
+                if let Some(session) = req.extensions().get::<Session>() {
+                    session.remove(SESSION_AUTHOR);
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(next.call(req).await?.into_response())
+}
+
+/// Middleware authenticating `Authorization: Bearer` tokens, which must
+/// carry the [`required_scope`] of the request. Requests without the header
+/// pass through untouched.
+pub async fn bearer_auth<E: Endpoint>(next: E, mut req: Request) -> poem::Result<Response> {
+    let secret = match req.headers().get(header::AUTHORIZATION) {
+        None => return Ok(next.call(req).await?.into_response()),
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|secret| secret.trim().to_owned())
+            .ok_or(Error::Unauthorized)?,
+    };
+    let state = req.data::<AppStateM>().expect("AppStateM is registered");
+    let (author, token) = state.token_service.authenticate_token(&secret).await?;
+    let scope = required_scope(req.method(), req.uri().path());
+    if !token.allows(scope) {
+        return Err(Error::Forbidden(format!("the token lacks the `{scope}` scope")).into());
+    }
+    req.extensions_mut().insert(CurrentAuthor(author));
+    Ok(next.call(req).await?.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_token, hash_password, hash_token, required_scope, verify_password, Method, Scope,
+    };
+
+    #[test]
+    fn passwords_are_salted_and_verified() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert_ne!(hash, hash_password("correct horse").unwrap());
+        assert!(verify_password(&hash, "correct horse"));
+        assert!(!verify_password(&hash, "battery staple"));
+        assert!(!verify_password("not a hash", "correct horse"));
+    }
+
+    #[test]
+    fn tokens_are_random_and_hashed() {
+        let token = generate_token();
+        assert!(token.starts_with("pa_") && token.len() == 67);
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_eq!(hash_token(&token).len(), 64);
+    }
+
+    #[test]
+    fn scopes_by_request() {
+        for (method, path, scope) in [
+            (Method::GET, "/articles/1", Scope::ArticlesRead),
+            (Method::GET, "/authors", Scope::ArticlesRead),
+            (Method::POST, "/articles", Scope::ArticlesWrite),
+            (
+                Method::DELETE,
+                "/articles/1/tags/rust",
+                Scope::ArticlesWrite,
+            ),
+            (Method::PATCH, "/authors/1", Scope::Admin),
+            (Method::GET, "/tokens", Scope::Admin),
+            (Method::GET, "/outbox", Scope::Admin),
+            (Method::GET, "/articlesx", Scope::ArticlesRead),
+            (Method::POST, "/articlesx", Scope::Admin),
+        ] {
+            assert_eq!(required_scope(&method, path), scope, "{method} {path}");
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Diffs between article revisions.
+//!
+//! A revision is compared as one document: the title, a blank line, then the
+//! content.
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::domain::revision;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A unified diff over lines, as printed by `diff -u`.
+    Unified,
+    /// Runs of unchanged, inserted and deleted words.
+    Words,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub op: Op,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diff {
+    Unified(String),
+    Words(Vec<Change>),
+}
+
+fn document(rev: &revision::Model) -> String {
+    format!(
+        "{}\n\n{}\n",
+        rev.title,
+        rev.content.as_deref().unwrap_or_default()
+    )
+}
+
+pub fn diff(from: &revision::Model, to: &revision::Model, format: Format) -> Diff {
+    let (old, new) = (document(from), document(to));
+    match format {
+        Format::Unified => Diff::Unified(
+            TextDiff::from_lines(&old, &new)
+                .unified_diff()
+                .header(&format!("rev {}", from.rev), &format!("rev {}", to.rev))
+                .to_string(),
+        ),
+        Format::Words => Diff::Words(words(&old, &new)),
+    }
+}
+
+/// Consecutive words with the same operation are merged into one change.
+fn words(old: &str, new: &str) -> Vec<Change> {
+    let mut changes: Vec<Change> = vec![];
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        let op = match change.tag() {
+            ChangeTag::Equal => Op::Equal,
+            ChangeTag::Insert => Op::Insert,
+            ChangeTag::Delete => Op::Delete,
+        };
+        match changes.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(change.value()),
+            _ => changes.push(Change {
+                op,
+                text: change.value().to_string(),
+            }),
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, Change, Diff, Format, Op};
+    use crate::domain::revision;
+
+    fn rev(rev: i32, title: &str, content: Option<&str>) -> revision::Model {
+        revision::Model {
+            id: rev,
+            article_id: 1,
+            rev,
+            title: title.to_string(),
+            content: content.map(str::to_string),
+            content_html: None,
+            editor_id: None,
+            created_at: Default::default(),
+        }
+    }
+
+    #[test]
+    fn unified() {
+        let from = rev(1, "Title", Some("one\ntwo"));
+        let to = rev(2, "Title", Some("one\n2"));
+        assert_eq!(
+            diff(&from, &to, Format::Unified),
+            Diff::Unified(
+                "--- rev 1\n+++ rev 2\n@@ -1,4 +1,4 @@\n Title\n \n one\n-two\n+2\n".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn words() {
+        let from = rev(1, "Hello world", None);
+        let to = rev(2, "Hello brave new world", None);
+        let Diff::Words(changes) = diff(&from, &to, Format::Words) else {
+            panic!("expected a word diff");
+        };
+        assert_eq!(
+            changes[..2],
+            [
+                Change {
+                    op: Op::Equal,
+                    text: "Hello ".to_string()
+                },
+                Change {
+                    op: Op::Insert,
+                    text: "brave new ".to_string()
+                },
+            ]
+        );
+    }
+}
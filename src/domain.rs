@@ -1,8 +1,11 @@
 pub mod article {
 
+    use chrono::Datelike;
     use sea_orm::entity::prelude::*;
     use serde::{Deserialize, Serialize};
 
+    use crate::slug;
+
     #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
     #[sea_orm(table_name = "articles")]
     pub struct Model {
@@ -10,20 +13,161 @@ pub mod article {
         #[serde(skip_deserializing)]
         pub id: i32,
         pub title: String,
+        /// Unique, derived from the title; old slugs are kept in `article_slugs`.
+        #[sea_orm(unique)]
+        pub slug: String,
+        /// Markdown.
         pub content: Option<String>,
+        /// `content` rendered and sanitized, as cached on the latest revision.
+        #[sea_orm(column_type = "Text", nullable)]
+        pub content_html: Option<String>,
+        pub author_id: Option<i32>,
+        pub status: Status,
+        /// When the article was last published.
+        pub published_at: Option<DateTimeUtc>,
+        /// When a scheduled article is due to be published.
+        pub publish_at: Option<DateTimeUtc>,
     }
     impl Model {
         pub(crate) fn from(am: ActiveModel) -> Self {
             Self {
                 id: am.id.unwrap(),
                 title: am.title.unwrap(),
+                slug: am.slug.unwrap(),
                 content: am.content.unwrap(),
+                content_html: am.content_html.unwrap(),
+                author_id: am.author_id.unwrap(),
+                status: am.status.unwrap(),
+                published_at: am.published_at.unwrap(),
+                publish_at: am.publish_at.unwrap(),
+            }
+        }
+
+        /// The public URL, `/posts/:year/:slug`, once the article is published.
+        pub fn permalink(&self) -> Option<String> {
+            match (self.status, self.published_at) {
+                (Status::Published, Some(at)) => Some(format!(
+                    "/posts/{}/{}",
+                    at.year(),
+                    slug::to_path(&self.slug)
+                )),
+                _ => None,
             }
         }
     }
 
+    /// Only published articles are listed publicly.
+    #[derive(
+        Copy,
+        Clone,
+        Debug,
+        Default,
+        PartialEq,
+        Eq,
+        EnumIter,
+        DeriveActiveEnum,
+        Deserialize,
+        Serialize,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+    #[serde(rename_all = "snake_case")]
+    pub enum Status {
+        #[default]
+        #[sea_orm(string_value = "draft")]
+        Draft,
+        #[sea_orm(string_value = "in_review")]
+        InReview,
+        #[sea_orm(string_value = "published")]
+        Published,
+        #[sea_orm(string_value = "archived")]
+        Archived,
+    }
+
+    impl Status {
+        /// The workflow: drafts go to review or straight to published,
+        /// published articles are archived, archived ones are reopened as drafts.
+        pub fn can_become(self, next: Status) -> bool {
+            use Status::*;
+            matches!(
+                (self, next),
+                (Draft, InReview)
+                    | (Draft, Published)
+                    | (InReview, Draft)
+                    | (InReview, Published)
+                    | (Published, Archived)
+                    | (Archived, Draft)
+            )
+        }
+    }
+
+    /// An article with its author eager-loaded, serialized as the article
+    /// fields plus an embedded `author` object.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+    pub struct WithAuthor {
+        #[serde(flatten)]
+        pub article: Model,
+        pub author: Option<super::author::Model>,
+    }
+
+    /// A full-text search match. `snippet` is HTML with the matches in `<mark>`,
+    /// a higher `score` is a better match.
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    pub struct SearchHit {
+        #[serde(flatten)]
+        pub article: Model,
+        pub score: f64,
+        pub snippet: String,
+    }
+
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-    pub enum Relation {}
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::AuthorId",
+            to = "super::author::Column::Id",
+            on_delete = "SetNull"
+        )]
+        Author,
+        #[sea_orm(has_many = "super::comment::Entity")]
+        Comment,
+        #[sea_orm(has_many = "super::revision::Entity")]
+        Revision,
+        #[sea_orm(has_many = "super::article_slug::Entity")]
+        OldSlug,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Author.def()
+        }
+    }
+
+    impl Related<super::comment::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Comment.def()
+        }
+    }
+
+    impl Related<super::revision::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Revision.def()
+        }
+    }
+
+    impl Related<super::article_slug::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::OldSlug.def()
+        }
+    }
+
+    impl Related<super::tag::Entity> for Entity {
+        fn to() -> RelationDef {
+            super::article_tag::Relation::Tag.def()
+        }
+        fn via() -> Option<RelationDef> {
+            Some(super::article_tag::Relation::Article.def().rev())
+        }
+    }
 
     impl ActiveModelBehavior for ActiveModel {}
 }
@@ -42,10 +186,594 @@ pub mod author {
         pub first_name: String,
         pub last_name: String,
         pub email: String,
+        /// Argon2 hash; authors without one can't log in.
+        #[sea_orm(column_type = "Text", nullable)]
+        #[serde(skip)]
+        pub password_hash: Option<String>,
+        pub role: Role,
+    }
+    impl Model {
+        pub(crate) fn from(am: ActiveModel) -> Self {
+            Self {
+                id: am.id.unwrap(),
+                first_name: am.first_name.unwrap(),
+                last_name: am.last_name.unwrap(),
+                email: am.email.unwrap(),
+                password_hash: am.password_hash.unwrap(),
+                role: am.role.unwrap(),
+            }
+        }
+    }
+
+    /// What an author may do is decided by [`crate::policy`].
+    #[derive(
+        Copy,
+        Clone,
+        Debug,
+        Default,
+        PartialEq,
+        Eq,
+        EnumIter,
+        DeriveActiveEnum,
+        Deserialize,
+        Serialize,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+    #[serde(rename_all = "snake_case")]
+    pub enum Role {
+        #[sea_orm(string_value = "admin")]
+        Admin,
+        #[sea_orm(string_value = "editor")]
+        Editor,
+        #[default]
+        #[sea_orm(string_value = "author")]
+        Author,
+        #[sea_orm(string_value = "reader")]
+        Reader,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-    pub enum Relation {}
+    pub enum Relation {
+        #[sea_orm(has_many = "super::article::Entity")]
+        Article,
+        #[sea_orm(has_many = "super::api_token::Entity")]
+        ApiToken,
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Article.def()
+        }
+    }
+
+    impl Related<super::api_token::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::ApiToken.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod tag {
+
+    use sea_orm::entity::prelude::*;
+    use sea_orm::FromQueryResult;
+    use serde::{Deserialize, Serialize};
+
+    /// Tags are looked up by `slug`; `name` keeps the spelling first used.
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+    #[sea_orm(table_name = "tags")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        #[serde(skip_deserializing)]
+        pub id: i32,
+        #[sea_orm(unique)]
+        pub slug: String,
+        pub name: String,
+    }
+
+    /// A tag with the number of articles carrying it.
+    #[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize)]
+    pub struct WithCount {
+        pub id: i32,
+        pub slug: String,
+        pub name: String,
+        pub article_count: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::article_tag::Entity")]
+        ArticleTag,
+    }
+
+    impl Related<super::article_tag::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::ArticleTag.def()
+        }
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            super::article_tag::Relation::Article.def()
+        }
+        fn via() -> Option<RelationDef> {
+            Some(super::article_tag::Relation::Tag.def().rev())
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod comment {
+
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+    #[sea_orm(table_name = "comments")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        #[serde(skip_deserializing)]
+        pub id: i32,
+        pub article_id: i32,
+        /// The comment this one replies to, `None` for top-level comments.
+        pub parent_id: Option<i32>,
+        pub author_name: String,
+        #[sea_orm(column_type = "Text")]
+        pub body: String,
+        pub created_at: DateTimeUtc,
+    }
+
+    /// A comment with its replies, oldest first.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+    pub struct Thread {
+        #[serde(flatten)]
+        pub comment: Model,
+        pub replies: Vec<Thread>,
+    }
+
+    impl Thread {
+        /// Arranges the comments of one article into threads, keeping their order.
+        pub fn build(comments: Vec<Model>) -> Vec<Thread> {
+            let mut children: HashMap<Option<i32>, Vec<Model>> = HashMap::new();
+            for comment in comments {
+                children.entry(comment.parent_id).or_default().push(comment);
+            }
+            Self::replies_to(None, &mut children)
+        }
+
+        fn replies_to(
+            parent_id: Option<i32>,
+            children: &mut HashMap<Option<i32>, Vec<Model>>,
+        ) -> Vec<Thread> {
+            children
+                .remove(&parent_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|comment| Thread {
+                    replies: Self::replies_to(Some(comment.id), children),
+                    comment,
+                })
+                .collect()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::article::Entity",
+            from = "Column::ArticleId",
+            to = "super::article::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Article,
+        #[sea_orm(
+            belongs_to = "Entity",
+            from = "Column::ParentId",
+            to = "Column::Id",
+            on_delete = "Cascade"
+        )]
+        Parent,
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Article.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Immutable snapshots of an article, written on creation and on every update.
+pub mod revision {
+
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+    #[sea_orm(table_name = "article_revisions")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        #[serde(skip_deserializing)]
+        pub id: i32,
+        pub article_id: i32,
+        /// Numbered from 1 within each article.
+        pub rev: i32,
+        pub title: String,
+        pub content: Option<String>,
+        /// `content` rendered with [`crate::markdown::render`] when the
+        /// revision was written.
+        #[sea_orm(column_type = "Text", nullable)]
+        pub content_html: Option<String>,
+        /// The author who made the change, when known.
+        pub editor_id: Option<i32>,
+        pub created_at: DateTimeUtc,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::article::Entity",
+            from = "Column::ArticleId",
+            to = "super::article::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Article,
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::EditorId",
+            to = "super::author::Column::Id",
+            on_delete = "SetNull"
+        )]
+        Editor,
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Article.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Slugs an article had before its title changed, kept so old links redirect.
+pub mod article_slug {
+
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+    #[sea_orm(table_name = "article_slugs")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub article_id: i32,
+        #[sea_orm(unique)]
+        pub slug: String,
+        /// When the slug was retired.
+        pub created_at: DateTimeUtc,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::article::Entity",
+            from = "Column::ArticleId",
+            to = "super::article::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Article,
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Article.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Join table between articles and tags.
+pub mod article_tag {
+
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "article_tags")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub article_id: i32,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub tag_id: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::article::Entity",
+            from = "Column::ArticleId",
+            to = "super::article::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Article,
+        #[sea_orm(
+            belongs_to = "super::tag::Entity",
+            from = "Column::TagId",
+            to = "super::tag::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Tag,
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Article.def()
+        }
+    }
+
+    impl Related<super::tag::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Tag.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Personal API tokens, sent as `Authorization: Bearer <token>`. Only a
+/// SHA-256 hash of the token is stored.
+pub mod api_token {
+
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use std::fmt;
+    use std::str::FromStr;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+    #[sea_orm(table_name = "api_tokens")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub author_id: i32,
+        pub name: String,
+        #[sea_orm(unique)]
+        #[serde(skip)]
+        pub token_hash: String,
+        /// Space separated, see [`Scope`].
+        pub scopes: String,
+        pub created_at: DateTimeUtc,
+        pub expires_at: DateTimeUtc,
+    }
+
+    impl Model {
+        /// Unknown scopes are skipped.
+        pub fn scopes(&self) -> Vec<Scope> {
+            self.scopes
+                .split_whitespace()
+                .filter_map(|s| s.parse().ok())
+                .collect()
+        }
+
+        /// `admin` grants every scope.
+        pub fn allows(&self, scope: Scope) -> bool {
+            self.scopes()
+                .iter()
+                .any(|s| *s == scope || *s == Scope::Admin)
+        }
+
+        pub fn is_expired(&self, now: DateTimeUtc) -> bool {
+            self.expires_at <= now
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum Scope {
+        #[serde(rename = "articles:read")]
+        ArticlesRead,
+        #[serde(rename = "articles:write")]
+        ArticlesWrite,
+        #[serde(rename = "admin")]
+        Admin,
+    }
+
+    impl Scope {
+        pub const ALL: [Scope; 3] = [Scope::ArticlesRead, Scope::ArticlesWrite, Scope::Admin];
+
+        pub fn as_str(self) -> &'static str {
+            match self {
+                Scope::ArticlesRead => "articles:read",
+                Scope::ArticlesWrite => "articles:write",
+                Scope::Admin => "admin",
+            }
+        }
+
+        /// The space separated form stored in [`Model::scopes`].
+        pub fn join(scopes: &[Scope]) -> String {
+            let names: Vec<_> = scopes.iter().map(|s| s.as_str()).collect();
+            names.join(" ")
+        }
+    }
+
+    impl fmt::Display for Scope {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    impl FromStr for Scope {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Scope::ALL
+                .into_iter()
+                .find(|scope| scope.as_str() == s)
+                .ok_or_else(|| format!("unknown scope `{s}`"))
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::AuthorId",
+            to = "super::author::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Author,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Author.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod outbox {
+
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    /// An event about an article, written in the same transaction as the
+    /// change and delivered to the publishers afterwards.
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+    #[sea_orm(table_name = "outbox")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub event: Event,
+        pub article_id: i32,
+        pub state: State,
+        /// Failed deliveries so far.
+        pub attempts: i32,
+        /// When a pending entry is due.
+        pub next_attempt_at: DateTimeUtc,
+        #[sea_orm(column_type = "Text", nullable)]
+        pub last_error: Option<String>,
+        pub created_at: DateTimeUtc,
+        pub delivered_at: Option<DateTimeUtc>,
+    }
+
+    #[derive(
+        Copy, Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+    pub enum Event {
+        #[sea_orm(string_value = "article.published")]
+        #[serde(rename = "article.published")]
+        ArticlePublished,
+    }
+
+    #[derive(
+        Copy, Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+    #[serde(rename_all = "snake_case")]
+    pub enum State {
+        /// Waiting for its first or next attempt.
+        #[sea_orm(string_value = "pending")]
+        Pending,
+        #[sea_orm(string_value = "delivered")]
+        Delivered,
+        /// Gave up after too many attempts, until replayed.
+        #[sea_orm(string_value = "dead")]
+        Dead,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::article::Entity",
+            from = "Column::ArticleId",
+            to = "super::article::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Article,
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Article.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod publication {
+
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    /// Where an article was posted on one channel of the publishers.
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+    #[sea_orm(table_name = "publications")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub article_id: i32,
+        /// The name of the publisher, as configured in `PUBLISHERS`.
+        pub channel: String,
+        pub state: State,
+        /// The id of the post on the channel, when it tells.
+        pub remote_id: Option<String>,
+        pub remote_url: Option<String>,
+        /// Every try, successful or not.
+        pub attempts: i32,
+        #[sea_orm(column_type = "Text", nullable)]
+        pub last_error: Option<String>,
+        /// When the article was last posted successfully.
+        pub posted_at: Option<DateTimeUtc>,
+        pub updated_at: DateTimeUtc,
+    }
+
+    impl Model {
+        /// Whether the article was posted since it was last published.
+        pub fn is_current(&self, article: &super::article::Model) -> bool {
+            match (self.state, self.posted_at, article.published_at) {
+                (State::Posted, Some(posted), Some(published)) => posted >= published,
+                _ => false,
+            }
+        }
+    }
+
+    #[derive(
+        Copy, Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+    #[serde(rename_all = "snake_case")]
+    pub enum State {
+        #[sea_orm(string_value = "posted")]
+        Posted,
+        /// The last try failed, see `last_error`.
+        #[sea_orm(string_value = "failed")]
+        Failed,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::article::Entity",
+            from = "Column::ArticleId",
+            to = "super::article::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Article,
+    }
+
+    impl Related<super::article::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Article.def()
+        }
+    }
 
     impl ActiveModelBehavior for ActiveModel {}
 }
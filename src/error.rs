@@ -0,0 +1,154 @@
+//! Error type carried through the repository, service and API layers.
+
+use poem::error::ResponseError;
+use poem::http::{header, StatusCode};
+use poem::{IntoResponse, Response};
+use poem_openapi::Object;
+use sea_orm::{DbErr, SqlErr};
+use serde::Serialize;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The named resource does not exist.
+    #[error("{0} not found")]
+    NotFound(&'static str),
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("authentication required")]
+    Unauthorized,
+    /// Authenticated, but not allowed to do this.
+    #[error("{0}")]
+    Forbidden(String),
+    /// Anything the client can't act on; the details are only logged.
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<DbErr> for Error {
+    fn from(e: DbErr) -> Self {
+        match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => {
+                Error::Conflict("a record with the same unique value already exists".to_string())
+            }
+            Some(SqlErr::ForeignKeyConstraintViolation(_)) => {
+                Error::Validation("a referenced record does not exist".to_string())
+            }
+            _ => Error::Internal(e.into()),
+        }
+    }
+}
+
+/// RFC 7807 problem details, sent as `application/problem+json`.
+#[derive(Debug, Clone, PartialEq, Eq, Object, Serialize)]
+pub struct Problem {
+    #[oai(rename = "type")]
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub status: u16,
+    #[oai(skip_serializing_if_is_none)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+impl Problem {
+    pub fn new(status: StatusCode, detail: Option<String>) -> Self {
+        Self {
+            kind: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail,
+        }
+    }
+
+    pub fn into_response(self) -> Response {
+        Response::builder()
+            .status(StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR))
+            .header(header::CONTENT_TYPE, PROBLEM_JSON)
+            .body(serde_json::to_vec(&self).unwrap_or_default())
+    }
+}
+
+impl Error {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::Conflict(_) => StatusCode::CONFLICT,
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::Forbidden(_) => StatusCode::FORBIDDEN,
+            Error::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Internal errors are logged here and reach the client without details.
+    pub fn problem(&self) -> Problem {
+        let detail = match self {
+            Error::Internal(e) => {
+                tracing::error!("internal error: {e:#}");
+                None
+            }
+            other => Some(other.to_string()),
+        };
+        Problem::new(self.status(), detail)
+    }
+}
+
+impl ResponseError for Error {
+    fn status(&self) -> StatusCode {
+        Error::status(self)
+    }
+
+    fn as_response(&self) -> Response {
+        self.problem().into_response()
+    }
+}
+
+/// Renders any error that escaped the endpoints as problem details. Errors that
+/// already carry a response, like `ApiError` or [`Error`], are passed through.
+pub async fn problem_response(err: poem::Error) -> Response {
+    if err.is_from_response() || err.is::<Error>() {
+        return err.into_response();
+    }
+    let status = err.status();
+    // Not an error, e.g. a `304 Not Modified` from an ETag match.
+    if !status.is_client_error() && !status.is_server_error() {
+        return status.into_response();
+    }
+    let detail = match status.is_server_error() {
+        true => {
+            tracing::error!("internal error: {err}");
+            None
+        }
+        false => Some(err.to_string()),
+    };
+    Problem::new(status, detail).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Problem};
+    use poem::http::StatusCode;
+
+    #[test]
+    fn internal_details_are_hidden() {
+        let problem = Error::Internal(anyhow::anyhow!("disk I/O error at /var/db")).problem();
+        assert_eq!(
+            problem,
+            Problem::new(StatusCode::INTERNAL_SERVER_ERROR, None)
+        );
+    }
+
+    #[test]
+    fn client_errors_keep_details() {
+        let problem = Error::NotFound("article").problem();
+        assert_eq!(problem.status, 404);
+        assert_eq!(problem.detail.as_deref(), Some("article not found"));
+    }
+}
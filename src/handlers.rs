@@ -1,190 +1,868 @@
-use poem::error::{InternalServerError, NotFoundError};
+#![allow(clippy::result_large_err)]
+
+use poem::error::InternalServerError;
 use poem::http::StatusCode;
 use poem::middleware::{TokioMetrics, Tracing};
-use poem::web::{Data, Form, Html, Json, Path, Query};
-use poem::{get, handler, post, Endpoint, EndpointExt, IntoResponse, Response, Result, Route};
+use poem::session::Session;
+use poem::web::{Data, Form, Html, Path, Query, Redirect};
+use poem::{
+    get, handler, post, Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Request, Response,
+    Result, Route,
+};
+use sea_orm::Iterable;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tera::Context;
 
-use crate::domain::article;
-use crate::AppStateM;
+use crate::auth::{self, CurrentAuthor};
+use crate::domain::{article, author};
+use crate::error::Error;
+use crate::pagination::Params;
+use crate::repositories::{ArticleFilter, ArticleUpdate};
+use crate::session::Flash;
+use crate::{api, assets, error, slug, AppStateM};
+
+#[derive(Deserialize)]
+pub struct IndexParams {
+    page: Option<i64>,
+    posts_per_page: Option<i64>,
+}
+
+/// The new and edit forms; only the edit form has a `status`.
+#[derive(Deserialize)]
+pub struct ArticleForm {
+    title: String,
+    content: String,
+    status: Option<article::Status>,
+}
+
+impl ArticleForm {
+    /// An empty textarea clears the content.
+    fn content(&self) -> Option<String> {
+        Some(self.content.clone()).filter(|content| !content.trim().is_empty())
+    }
+}
 
-#[derive(Deserialize, Serialize)]
-pub struct Params {
-    page: i32,
-    page_size: i32,
+#[derive(Deserialize)]
+pub struct LoginForm {
+    email: String,
+    password: String,
+}
+
+/// An index row, linked to its permalink once published.
+#[derive(Serialize)]
+struct Post {
+    #[serde(flatten)]
+    article: article::Model,
+    permalink: Option<String>,
+}
+
+fn viewer(req: &Request) -> Option<&author::Model> {
+    req.extensions()
+        .get::<CurrentAuthor>()
+        .map(|CurrentAuthor(author)| author)
+}
+
+/// Renders a page with the logged in author as `viewer`, showing the
+/// pending flash if there is one.
+fn render(state: &AppStateM, req: &Request, template: &str, ctx: &Context) -> Result<Html<String>> {
+    let mut ctx = ctx.clone();
+    if let Some(CurrentAuthor(viewer)) = req.extensions().get::<CurrentAuthor>() {
+        ctx.insert("viewer", viewer);
+    }
+    if let Some(flash) = req.extensions().get::<Session>().and_then(Flash::take) {
+        ctx.insert("flash", &flash);
+    }
+    state
+        .templates
+        .render(template, &ctx)
+        .map_err(InternalServerError)
+        .map(Html)
+}
+
+/// Invalid input is shown on the form again, other errors are problems.
+fn form_error(
+    state: &AppStateM,
+    req: &Request,
+    template: &str,
+    mut ctx: Context,
+    e: Error,
+) -> Result<Response> {
+    match e {
+        Error::Validation(ref message)
+        | Error::Conflict(ref message)
+        | Error::Forbidden(ref message) => {
+            ctx.insert("error", message);
+            Ok(render(state, req, template, &ctx)?
+                .with_status(e.status())
+                .into_response())
+        }
+        e => Err(e.into()),
+    }
+}
+
+#[handler]
+pub async fn get_tokyo_metric() -> &'static str {
+    "tokyo_metric"
 }
 
 #[handler]
-pub async fn create_article(
+async fn index_view(
     state: Data<&AppStateM>,
-    form: Form<article::Model>,
+    req: &Request,
+    Query(params): Query<IndexParams>,
 ) -> Result<impl IntoResponse> {
-    let result = state.service.create_article(form.title.as_str()).await;
-    Ok(Json(
-        result.map(article::Model::from).map_err(|e| e.to_string()),
-    ))
+    let params = Params::new(params.page, params.posts_per_page);
+    let posts = state
+        .service
+        .list_articles(&ArticleFilter::published(), params, viewer(req))
+        .await?;
+    let items: Vec<Post> = posts
+        .items
+        .into_iter()
+        .map(|article| Post {
+            permalink: article.permalink(),
+            article,
+        })
+        .collect();
+    let mut ctx = Context::new();
+    ctx.insert("posts", &items);
+    ctx.insert("page", &posts.page);
+    ctx.insert("num_pages", &posts.total_pages.max(1));
+    ctx.insert("posts_per_page", &posts.page_size);
+    render(&state, req, "index.html.tera", &ctx)
+}
+
+/// Article counts by status, and tags by use. Unpublished articles are
+/// counted as far as the viewer may list them.
+#[handler]
+async fn stats_view(state: Data<&AppStateM>, req: &Request) -> Result<Html<String>> {
+    let mut counts = vec![];
+    for status in article::Status::iter() {
+        let filter = ArticleFilter {
+            status: Some(status),
+            ..Default::default()
+        };
+        let page = state
+            .service
+            .list_articles(&filter, Params::new(None, Some(1)), viewer(req))
+            .await;
+        match page {
+            Ok(page) => counts.push((status, page.total_items)),
+            Err(Error::Unauthorized | Error::Forbidden(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let tags = state.tag_service.list_tags().await?;
+    let mut ctx = Context::new();
+    ctx.insert("counts", &counts);
+    ctx.insert("tags", &tags);
+    render(&state, req, "stats.html.tera", &ctx)
 }
 
 #[handler]
-pub async fn list_articles(
+fn new_view(state: Data<&AppStateM>, req: &Request) -> Result<Html<String>> {
+    render(&state, req, "new.html.tera", &Context::new())
+}
+
+/// New articles start as drafts, so this continues on the edit form.
+#[handler]
+async fn create(
     state: Data<&AppStateM>,
-    Query(params): Query<Params>,
-) -> Result<impl IntoResponse> {
-    let result = state
+    req: &Request,
+    session: &Session,
+    CurrentAuthor(author): CurrentAuthor,
+    Form(form): Form<ArticleForm>,
+) -> Result<Response> {
+    match state
         .service
-        .list_articles(params.page, params.page_size)
-        .await;
-    Ok(Json(result.map_err(|e| e.to_string())))
+        .create_article(&form.title, form.content(), Some(author.id), &author)
+        .await
+    {
+        Ok(created) => {
+            Flash::success("Draft created.").set(session);
+            Ok(Redirect::see_other(format!("/{}", created.id.unwrap())).into_response())
+        }
+        Err(e) => {
+            let mut ctx = Context::new();
+            ctx.insert("title", &form.title);
+            ctx.insert("content", &form.content);
+            form_error(&state, req, "new.html.tera", ctx, e)
+        }
+    }
+}
+
+fn edit_context(article: &article::Model) -> Context {
+    // The current status and the ones it can move to.
+    let statuses: Vec<_> = article::Status::iter()
+        .filter(|s| *s == article.status || article.status.can_become(*s))
+        .collect();
+    let mut ctx = Context::new();
+    ctx.insert("article", article);
+    ctx.insert("statuses", &statuses);
+    ctx.insert("permalink", &article.permalink());
+    ctx
 }
 
 #[handler]
-pub async fn get_article_by_id(
+async fn edit_view(
     state: Data<&AppStateM>,
+    req: &Request,
+    CurrentAuthor(author): CurrentAuthor,
     Path(id): Path<i32>,
-) -> Result<impl IntoResponse> {
-    let result = state.service.get_article_by_id(id).await;
-    Ok(Json(result.map_err(|e| e.to_string())))
+) -> Result<Html<String>> {
+    let article = state.service.get_editable_article(id, &author).await?;
+    render(&state, req, "edit.html.tera", &edit_context(&article))
 }
 
+/// Saves the title and content, then moves the article to the chosen status.
+/// The text is stored by the time a status change is refused, so that is
+/// reported in the flash rather than on the form.
 #[handler]
-pub async fn get_tokyo_metric() -> &'static str {
-    "tokyo_metric"
+async fn update(
+    state: Data<&AppStateM>,
+    req: &Request,
+    session: &Session,
+    CurrentAuthor(editor): CurrentAuthor,
+    Path(id): Path<i32>,
+    Form(form): Form<ArticleForm>,
+) -> Result<Response> {
+    let update = ArticleUpdate {
+        title: Some(form.title.clone()),
+        content: Some(form.content()),
+        editor_id: Some(editor.id),
+    };
+    let article = match state.service.update_article(id, &update, &editor).await {
+        Ok(article) => article,
+        Err(e) => {
+            // Show what was submitted on the current article.
+            let mut article = state.service.get_editable_article(id, &editor).await?;
+            article.title = form.title;
+            article.content = Some(form.content);
+            return form_error(&state, req, "edit.html.tera", edit_context(&article), e);
+        }
+    };
+    let flash = match form.status {
+        Some(status) if status != article.status => {
+            match state.service.transition_article(id, status, &editor).await {
+                Ok(_) => Flash::success("Post saved."),
+                Err(
+                    Error::Validation(message)
+                    | Error::Conflict(message)
+                    | Error::Forbidden(message),
+                ) => Flash::error(format!(
+                    "Post saved, but its status was not changed: {message}"
+                )),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        _ => Flash::success("Post saved."),
+    };
+    flash.set(session);
+    Ok(Redirect::see_other(format!("/{id}")).into_response())
 }
 
 #[handler]
-fn index_view(state: Data<&AppStateM>) -> Result<impl IntoResponse> {
-    let tera = &state.templates;
-    tera.render("index.html.tera", &Context::new())
-        .map_err(InternalServerError)
-        .map(Html)
+async fn delete(
+    state: Data<&AppStateM>,
+    session: &Session,
+    CurrentAuthor(author): CurrentAuthor,
+    Path(id): Path<i32>,
+) -> Result<Redirect> {
+    state.service.delete_article(id, &author).await?;
+    Flash::success("Post deleted.").set(session);
+    Ok(Redirect::see_other("/"))
 }
 
 #[handler]
-fn stats_view(state: Data<&AppStateM>) -> Result<impl IntoResponse> {
-    let tera = &state.templates;
-    tera.render("stats.html.tera", &Context::new())
-        .map_err(InternalServerError)
-        .map(Html)
+async fn article_view(
+    state: Data<&AppStateM>,
+    req: &Request,
+    Path(id): Path<i32>,
+) -> Result<Html<String>> {
+    render_article(&state, req, id).await
 }
 
+/// Published articles at `/posts/:year/:slug`. Old slugs and wrong years
+/// redirect permanently to the current permalink.
 #[handler]
-fn articles_view(state: Data<&AppStateM>) -> Result<impl IntoResponse> {
-    let tera = &state.templates;
-    tera.render("article.html.tera", &Context::new())
-        .map_err(InternalServerError)
-        .map(Html)
+async fn permalink_view(
+    state: Data<&AppStateM>,
+    req: &Request,
+    Path((year, slug)): Path<(i32, String)>,
+) -> Result<Response> {
+    let article = state
+        .service
+        .get_article_by_slug(&slug, viewer(req))
+        .await?;
+    let Some(permalink) = article.permalink() else {
+        return Err(error::Error::NotFound("article").into());
+    };
+    if permalink != format!("/posts/{year}/{}", slug::to_path(&slug)) {
+        return Ok(Redirect::moved_permanent(permalink).into_response());
+    }
+    Ok(render_article(&state, req, article.id)
+        .await?
+        .into_response())
+}
+
+async fn render_article(state: &AppStateM, req: &Request, id: i32) -> Result<Html<String>> {
+    let article = state
+        .service
+        .get_article_with_author(id, viewer(req))
+        .await?;
+    let threads = state.comment_service.list_comments(id, viewer(req)).await?;
+    let mut ctx = Context::new();
+    ctx.insert("article", &article);
+    ctx.insert("threads", &threads);
+    render(state, req, "article.html.tera", &ctx)
+}
+
+#[handler]
+fn login_view(state: Data<&AppStateM>, req: &Request) -> Result<Html<String>> {
+    render(&state, req, "login.html.tera", &Context::new())
+}
+
+#[handler]
+async fn login(
+    state: Data<&AppStateM>,
+    req: &Request,
+    session: &Session,
+    Form(form): Form<LoginForm>,
+) -> Result<Response> {
+    match state
+        .author_service
+        .authenticate(&form.email, &form.password)
+        .await
+    {
+        Ok(author) => {
+            auth::log_in(session, &author);
+            Flash::success(format!("Welcome back, {}.", author.first_name)).set(session);
+            Ok(Redirect::see_other("/").into_response())
+        }
+        Err(Error::Unauthorized) => {
+            let mut ctx = Context::new();
+            ctx.insert("email", &form.email);
+            ctx.insert("error", "wrong email or password");
+            Ok(render(&state, req, "login.html.tera", &ctx)?
+                .with_status(StatusCode::UNAUTHORIZED)
+                .into_response())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[handler]
+fn logout(session: &Session) -> Redirect {
+    auth::log_out(session);
+    Flash::success("Logged out.").set(session);
+    Redirect::see_other("/")
+}
+
+/// Sends anonymous visitors of the editing pages to the login form.
+async fn login_required<E: Endpoint>(next: E, req: Request) -> Result<Response> {
+    if req.extensions().get::<CurrentAuthor>().is_none() {
+        if let Some(session) = req.extensions().get::<Session>() {
+            Flash::error("Log in to edit posts.").set(session);
+        }
+        return Ok(Redirect::see_other("/login").into_response());
+    }
+    Ok(next.call(req).await?.into_response())
 }
 
 pub fn config_router(state: AppStateM) -> impl Endpoint<Output = Response> {
     let tokyo_metric = TokioMetrics::new();
-    let list_articles_metric = TokioMetrics::new();
-    Route::new()
+    let api_service = api::service();
+    let spec = api_service.spec_endpoint();
+    let swagger_ui = api_service.swagger_ui();
+    // Nesting the API at `/` would shadow the index page, so it is mounted
+    // once per top-level resource instead.
+    let api = Arc::new(api_service.into_endpoint());
+    let mut route = Route::new();
+    for prefix in api::PREFIXES {
+        route = route.nest_no_strip(prefix, api.clone());
+    }
+    route
         .at("/", get(index_view))
         .at("/stats", get(stats_view))
-        .at("/articles_view", get(articles_view))
+        .at("/articles_view/:id", get(article_view))
+        .at("/posts/:year/:slug", get(permalink_view))
+        .at("/login", get(login_view).post(login))
+        .at("/logout", post(logout))
+        .at("/new", get(new_view).post(create).around(login_required))
         .at(
-            "/articles",
-            post(create_article)
-                .get(list_articles)
-                .with(list_articles_metric),
+            "/:id<\\d+>",
+            get(edit_view).post(update).around(login_required),
         )
-        .at("/articles/:id", get(get_article_by_id))
-        // .at("/new", new)
-        // .at("/:id", get(edit).post(update))
-        // .nest(
-        //     "/static",
-        //     StaticFilesEndpoint::new(format!("{}/static", resources_path)),
-        // )
+        .at("/:id<\\d+>/delete", post(delete).around(login_required))
+        .at("/api/openapi.json", spec)
+        .nest("/api/docs", swagger_ui)
+        .nest("/static", assets::static_files(&state.resources))
         .at("/metrics/tokyo", tokyo_metric.exporter())
         .at("/tokyo", get(get_tokyo_metric))
         .with(tokyo_metric)
+        .around(auth::resolve_author)
+        .around(auth::bearer_auth)
+        .with(state.session.middleware())
         .with(Tracing)
         .data(state)
-        .catch_error(|_: NotFoundError| async move {
-            Response::builder()
-                .status(StatusCode::NOT_FOUND)
-                .body("Error")
-        })
+        .catch_all_error(error::problem_response)
 }
 
 #[cfg(test)]
-pub mod tests {
+mod tests {
     use std::sync::Arc;
 
-    use crate::domain::article;
+    use crate::api::tests::{ada, log_in, session_cookie, with_login};
+    use crate::domain::{article, comment, tag};
+    use crate::error::Error;
+    use crate::pagination::{Page, Params};
+    use crate::repositories::ArticleUpdate;
+    use crate::services::tests::draft;
+    use crate::services::{
+        MockArticleServiceTrait, MockAuthorServiceTrait, MockCommentServiceTrait,
+        MockOutboxServiceTrait, MockPublicationServiceTrait, MockSocialMediaPublisherTrait,
+        MockTagServiceTrait, MockTokenServiceTrait,
+    };
+    use crate::session::SessionConfig;
+    use crate::slug::slugify;
+    use crate::{assets, AppStateM};
     use mockall::predicate::*;
-    use poem::{http::StatusCode, test::TestClient, Endpoint, Response};
-    use sea_orm::Set;
-    use tera::Tera;
-
-    // fn get_client(
-    //     state: AppState,
-    //     mock: MockArticleServiceTrait,
-    // ) -> TestClient<impl Endpoint<Output = Response>> {
-    //     let mut mock_service = MokArticleServiceTr::new();
-    //     let mock_app_state = AppStateM {
-    //         service: Arc::new(mock_service),
-    //         publisher: Arc::new(MockSocialMediaPublisherTrait {}),
-    //         templates: Tera::new("./templates/**/*").unwrap(),
-    //     };
-
-    //     let router = create_router(mock_app_state);
-    //     TestClient::new(router)
-    // }
-
-    // #[tokio::test]
-    // async fn get_article_by_id() {
-    //     let mut mock = MockArticleServiceTrait::new();
-    //     mock.get_article_by_id(1)
-    //         .await
-    //         .with(always())
-    //         .returning(|id| {
-    //             Ok(Some(article::Model {
-    //                 id,
-    //                 title: "title".to_string(),
-    //                 content: None,
-    //             }))
-    //         });
-
-    //     let cli = get_client(AppState { templates: None }, mock);
-    //     let resp = cli.get("/articles/1").send().await;
-    //     resp.assert_status_is_ok();
-    // }
-
-    // #[tokio::test]
-    // async fn create_article() {
-    //     let mut mock = MockArticleServiceTrait::new();
-    //     mock.create_article("title")
-    //         .with(always())
-    //         .returning(|_title| {
-    //             Ok(article::ActiveModel {
-    //                 id: Set(1),
-    //                 title: Set(_title.to_string()),
-    //                 content: Set(None),
-    //             })
-    //         });
-
-    //     let cli = get_client(AppState { templates: None }, mock);
-    //     let resp = cli.post("/articles?title=artcile_title").send().await;
-    //     resp.assert_status(StatusCode::CREATED);
-    // }
-
-    // #[tokio::test]
-    // async fn list_articles() {
-    //     let mut mock = MockArticleServiceTrait::new();
-    //     mock.list_articles(0, 20)
-    //         .with(always(), always())
-    //         .returning(|page, page_size| {
-    //             Ok(vec![article::Model {
-    //                 id: 1,
-    //                 title: "tille".to_string(),
-    //                 content: None,
-    //             }])
-    //         });
-
-    //     let cli = get_client(AppState { templates: None }, mock);
-    //     let resp = cli.get("/articles?page=0&page_size=20").send().await;
-    //     resp.assert_status_is_ok();
-    // }
+    use poem::http::StatusCode;
+    use poem::test::TestClient;
+    use std::path::{Path, PathBuf};
+
+    fn state(mock: MockArticleServiceTrait, mock_comment: MockCommentServiceTrait) -> AppStateM {
+        AppStateM {
+            service: Arc::new(mock),
+            author_service: Arc::new(with_login(MockAuthorServiceTrait::new())),
+            tag_service: Arc::new(MockTagServiceTrait::new()),
+            comment_service: Arc::new(mock_comment),
+            token_service: Arc::new(MockTokenServiceTrait::new()),
+            outbox_service: Arc::new(MockOutboxServiceTrait::new()),
+            publication_service: Arc::new(MockPublicationServiceTrait::new()),
+            publisher: Arc::new(MockSocialMediaPublisherTrait::new()),
+            templates: assets::templates(Path::new("resources")).unwrap(),
+            resources: PathBuf::from("resources"),
+            session: SessionConfig::generate(),
+        }
+    }
+
+    #[tokio::test]
+    async fn index_view_pages() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_list_articles()
+            .with(always(), eq(Params::new(Some(2), Some(5))), always())
+            .returning(|_filter, params, _viewer| {
+                let items = vec![article::Model {
+                    status: article::Status::Published,
+                    ..titled(6, "sixth")
+                }];
+                Ok(Page::new(items, params, 6))
+            });
+        let cli = TestClient::new(super::config_router(state(
+            mock,
+            MockCommentServiceTrait::new(),
+        )));
+        let resp = cli.get("/?page=2&posts_per_page=5").send().await;
+        resp.assert_status_is_ok();
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("sixth"));
+        assert!(body.contains(r#"href="/?page=1&posts_per_page=5""#));
+        assert!(!body.contains("page=3"));
+    }
+
+    #[tokio::test]
+    async fn article_view_renders_thread() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_article_with_author()
+            .withf(|id, _viewer| *id == 1)
+            .returning(|id, _viewer| {
+                Ok(article::WithAuthor {
+                    article: article::Model {
+                        content: Some("*body*".to_string()),
+                        content_html: Some("<p><em>body</em></p>".to_string()),
+                        status: article::Status::Published,
+                        ..titled(id, "first")
+                    },
+                    author: None,
+                })
+            });
+        let mut mock_comment = MockCommentServiceTrait::new();
+        mock_comment
+            .expect_list_comments()
+            .returning(|article_id, _viewer| {
+                let comment = |id, parent_id, body: &str| comment::Model {
+                    id,
+                    article_id,
+                    parent_id,
+                    author_name: "Ada".to_string(),
+                    body: body.to_string(),
+                    created_at: "2026-10-18T12:00:00Z".parse().unwrap(),
+                };
+                Ok(comment::Thread::build(vec![
+                    comment(1, None, "question"),
+                    comment(2, Some(1), "<b>answer</b>"),
+                ]))
+            });
+
+        let cli = TestClient::new(super::config_router(state(mock, mock_comment)));
+        let resp = cli.get("/articles_view/1").send().await;
+        resp.assert_status_is_ok();
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("<p><em>body</em></p>"));
+        let question = body.find("question").unwrap();
+        let answer = body.find("&lt;b&gt;answer&lt;&#x2F;b&gt;").unwrap();
+        assert!(question < answer);
+        assert_eq!(body.matches(r#"<ul class="comments">"#).count(), 2);
+        assert!(body.contains("2026-10-18 12:00"));
+    }
+
+    #[tokio::test]
+    async fn permalinks_redirect_to_the_current_slug() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_article_by_slug()
+            .returning(|slug, _viewer| {
+                Ok(article::Model {
+                    status: match slug {
+                        "draft" => article::Status::Draft,
+                        _ => article::Status::Published,
+                    },
+                    published_at: Some("2026-10-18T12:00:00Z".parse().unwrap()),
+                    ..titled(1, "Hello")
+                })
+            });
+        let cli = TestClient::new(super::config_router(state(
+            mock,
+            MockCommentServiceTrait::new(),
+        )));
+
+        for path in ["/posts/2026/old-hello", "/posts/2025/hello"] {
+            let resp = cli.get(path).send().await;
+            resp.assert_status(StatusCode::MOVED_PERMANENTLY);
+            resp.assert_header("Location", "/posts/2026/hello");
+        }
+        let resp = cli.get("/posts/2026/draft").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    fn titled(id: i32, title: &str) -> article::Model {
+        article::Model {
+            title: title.to_string(),
+            slug: slugify(title),
+            ..draft(id, None)
+        }
+    }
+
+    #[tokio::test]
+    async fn new_article_form() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_create_article()
+            .with(
+                eq("Hello"),
+                eq(Some("*hi*".to_string())),
+                eq(Some(3)),
+                eq(ada(3)),
+            )
+            .returning(|title, _, _, _| Ok(titled(7, title).into()));
+        mock.expect_create_article()
+            .with(eq(" "), always(), eq(Some(3)), always())
+            .returning(|_, _, _, _| Err(Error::Validation("title must not be empty".to_string())));
+        let cli = TestClient::new(super::config_router(state(
+            mock,
+            MockCommentServiceTrait::new(),
+        )));
+
+        let cookie = log_in(&cli).await;
+        cli.get("/new")
+            .header("Cookie", &cookie)
+            .send()
+            .await
+            .assert_status_is_ok();
+        let resp = cli
+            .post("/new")
+            .header("Cookie", &cookie)
+            .form(&[("title", "Hello"), ("content", "*hi*")])
+            .send()
+            .await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+        resp.assert_header("Location", "/7");
+
+        let resp = cli
+            .post("/new")
+            .header("Cookie", &cookie)
+            .form(&[("title", " "), ("content", "<kept>")])
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("title must not be empty"));
+        assert!(body.contains("&lt;kept&gt;"));
+    }
+
+    #[tokio::test]
+    async fn edit_article_form() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_editable_article()
+            .with(eq(3), eq(ada(3)))
+            .returning(|id, _| Ok(titled(id, "Draft")));
+        mock.expect_update_article()
+            .with(
+                eq(3),
+                eq(ArticleUpdate {
+                    title: Some("Final".to_string()),
+                    content: Some(None),
+                    editor_id: Some(3),
+                }),
+                eq(ada(3)),
+            )
+            .times(1)
+            .returning(|id, _, _| Ok(titled(id, "Final")));
+        mock.expect_update_article()
+            .withf(|_, update, _| update.title.as_deref() == Some("Not mine"))
+            .returning(|id, _, _| {
+                Err(Error::Forbidden(format!(
+                    "the `author` role may not edit article {id}"
+                )))
+            });
+        mock.expect_transition_article()
+            .with(eq(3), eq(article::Status::Published), eq(ada(3)))
+            .times(1)
+            .returning(|id, status, _| {
+                Ok(article::Model {
+                    status,
+                    ..titled(id, "Final")
+                })
+            });
+        let cli = TestClient::new(super::config_router(state(
+            mock,
+            MockCommentServiceTrait::new(),
+        )));
+
+        let cookie = log_in(&cli).await;
+        let resp = cli.get("/3").header("Cookie", &cookie).send().await;
+        resp.assert_status_is_ok();
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains(r#"<option value="draft" selected>"#));
+        assert!(body.contains(r#"<option value="published">"#));
+        assert!(!body.contains("archived"));
+
+        let resp = cli
+            .post("/3")
+            .header("Cookie", &cookie)
+            .form(&[("title", "Final"), ("content", ""), ("status", "published")])
+            .send()
+            .await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+        resp.assert_header("Location", "/3");
+
+        let resp = cli
+            .post("/3")
+            .header("Cookie", &cookie)
+            .form(&[("title", "Not mine"), ("content", "")])
+            .send()
+            .await;
+        resp.assert_status(StatusCode::FORBIDDEN);
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("may not edit article 3"));
+    }
+
+    #[tokio::test]
+    async fn refused_status_change_after_saving() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_editable_article()
+            .returning(|id, _| Ok(titled(id, "Final")));
+        mock.expect_update_article()
+            .times(1)
+            .returning(|id, _, _| Ok(titled(id, "Final")));
+        mock.expect_transition_article()
+            .with(eq(3), eq(article::Status::Published), eq(ada(3)))
+            .times(1)
+            .returning(|id, _, _| {
+                Err(Error::Forbidden(format!(
+                    "the `author` role may not publish article {id}"
+                )))
+            });
+        let cli = TestClient::new(super::config_router(state(
+            mock,
+            MockCommentServiceTrait::new(),
+        )));
+
+        let cookie = log_in(&cli).await;
+        let resp = cli
+            .post("/3")
+            .header("Cookie", &cookie)
+            .form(&[("title", "Final"), ("content", ""), ("status", "published")])
+            .send()
+            .await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+        resp.assert_header("Location", "/3");
+
+        let resp = cli.get("/3").header("Cookie", &cookie).send().await;
+        resp.assert_status_is_ok();
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains(
+            "Post saved, but its status was not changed: \
+             the `author` role may not publish article 3"
+        ));
+        assert!(body.contains(r#"<option value="draft" selected>"#));
+    }
+
+    #[tokio::test]
+    async fn edit_form_of_another_authors_article() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_get_editable_article()
+            .with(eq(4), eq(ada(3)))
+            .returning(|_, _| Err(Error::NotFound("article")));
+        mock.expect_get_editable_article()
+            .with(eq(5), eq(ada(3)))
+            .returning(|id, _| {
+                Err(Error::Forbidden(format!(
+                    "the `author` role may not edit article {id}"
+                )))
+            });
+        let cli = TestClient::new(super::config_router(state(
+            mock,
+            MockCommentServiceTrait::new(),
+        )));
+
+        let cookie = log_in(&cli).await;
+        let resp = cli.get("/4").header("Cookie", &cookie).send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        let resp = cli.get("/5").header("Cookie", &cookie).send().await;
+        resp.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn flash_is_shown_once() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_delete_article()
+            .with(eq(5), eq(ada(3)))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let cli = TestClient::new(super::config_router(state(
+            mock,
+            MockCommentServiceTrait::new(),
+        )));
+        let cookie = log_in(&cli).await;
+
+        let resp = cli.post("/5/delete").header("Cookie", &cookie).send().await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+        resp.assert_header("Location", "/");
+
+        let resp = cli.get("/new").header("Cookie", &cookie).send().await;
+        resp.assert_status_is_ok();
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains(r#"<small class="field-success-flash">Post deleted.</small>"#));
+
+        let resp = cli.get("/new").header("Cookie", &cookie).send().await;
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(!body.contains("Post deleted."));
+    }
+
+    #[tokio::test]
+    async fn editing_needs_a_login() {
+        let cli = TestClient::new(super::config_router(state(
+            MockArticleServiceTrait::new(),
+            MockCommentServiceTrait::new(),
+        )));
+        let resp = cli.get("/new").send().await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+        resp.assert_header("Location", "/login");
+        let cookie = session_cookie(&resp);
+
+        let resp = cli.get("/login").header("Cookie", &cookie).send().await;
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("Log in to edit posts."));
+
+        let login = |password| {
+            cli.post("/login")
+                .header("Cookie", &cookie)
+                .form(&[("email", "ada@example.com"), ("password", password)])
+                .send()
+        };
+        let resp = login("battery staple").await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("wrong email or password"));
+        assert!(body.contains(r#"value="ada@example.com""#));
+
+        let resp = login("correct horse").await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+        resp.assert_header("Location", "/");
+        // Logging in starts a new session.
+        let cookie = session_cookie(&resp);
+        let resp = cli.get("/new").header("Cookie", &cookie).send().await;
+        resp.assert_status_is_ok();
+        let body = resp.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("Welcome back, Ada."));
+        assert!(body.contains(r#"<input type="submit" value="log out" />"#));
+
+        // A session id that doesn't match its signature is ignored.
+        let mut forged = cookie.clone();
+        let last = forged.pop().unwrap();
+        forged.push(if last == 'A' { 'B' } else { 'A' });
+        let resp = cli.get("/new").header("Cookie", forged).send().await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+
+        let resp = cli.post("/logout").header("Cookie", &cookie).send().await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+        let resp = cli.get("/new").header("Cookie", &cookie).send().await;
+        resp.assert_status(StatusCode::SEE_OTHER);
+    }
+
+    #[tokio::test]
+    async fn stats_view_counts() {
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_list_articles()
+            .returning(|filter, params, viewer| match (filter.status, viewer) {
+                (Some(article::Status::Published), _) => Ok(Page::new(vec![], params, 4)),
+                (_, None) => Err(Error::Unauthorized),
+                (_, Some(_)) => Ok(Page::new(vec![], params, 1)),
+            });
+        let mut mock_tag = MockTagServiceTrait::new();
+        mock_tag.expect_list_tags().returning(|| {
+            Ok(vec![tag::WithCount {
+                id: 1,
+                slug: "rust".to_string(),
+                name: "Rust".to_string(),
+                article_count: 2,
+            }])
+        });
+        let state = AppStateM {
+            tag_service: Arc::new(mock_tag),
+            ..state(mock, MockCommentServiceTrait::new())
+        };
+        let cli = TestClient::new(super::config_router(state));
+
+        let resp = cli.get("/stats").send().await;
+        resp.assert_status_is_ok();
+        let body = resp.0.into_body().into_string().await.unwrap();
+        // Anonymous visitors only see the published articles counted.
+        assert!(!body.contains("<td>in review</td>"));
+        assert!(body.contains("<td>4</td>"));
+        assert!(body.contains("<td>Rust</td>"));
+    }
+
+    #[tokio::test]
+    async fn static_files_are_cached() {
+        let cli = TestClient::new(super::config_router(state(
+            MockArticleServiceTrait::new(),
+            MockCommentServiceTrait::new(),
+        )));
+        let resp = cli.get("/static/css/style.css").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header("Cache-Control", assets::CACHE_CONTROL);
+        let etag = resp.0.headers().get("ETag").expect("an ETag").clone();
+
+        let resp = cli
+            .get("/static/css/style.css")
+            .header("If-None-Match", etag)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NOT_MODIFIED);
+
+        let resp = cli.get("/static/missing.css").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+    }
 }
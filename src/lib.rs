@@ -1,10 +1,30 @@
+pub mod api;
+pub mod assets;
+pub mod auth;
+pub mod diff;
 pub mod domain;
+pub mod error;
 pub mod handlers;
+pub mod markdown;
+pub mod migration;
+pub mod pagination;
+pub mod policy;
+pub mod publishers;
 pub mod repositories;
+pub mod scheduler;
+pub mod schema;
+pub mod search;
 pub mod services;
+pub mod session;
+pub mod slug;
 
-use crate::services::{ArticleServiceTrait, SocialMediaPublisherTrait};
-use std::{env, sync::Arc};
+use crate::publishers::PublisherSettings;
+use crate::services::{
+    ArticleServiceTrait, AuthorServiceTrait, CommentServiceTrait, OutboxServiceTrait,
+    PublicationServiceTrait, SocialMediaPublisherTrait, TagServiceTrait, TokenServiceTrait,
+};
+use crate::session::SessionConfig;
+use std::{env, io, path::PathBuf, sync::Arc, time::Duration};
 
 #[derive(Debug, Clone)]
 pub struct AppState {
@@ -13,22 +33,137 @@ pub struct AppState {
 #[derive(Debug, Clone)]
 pub struct AppStateM {
     pub service: Arc<dyn ArticleServiceTrait>,
+    pub author_service: Arc<dyn AuthorServiceTrait>,
+    pub tag_service: Arc<dyn TagServiceTrait>,
+    pub comment_service: Arc<dyn CommentServiceTrait>,
+    pub token_service: Arc<dyn TokenServiceTrait>,
+    pub outbox_service: Arc<dyn OutboxServiceTrait>,
+    pub publication_service: Arc<dyn PublicationServiceTrait>,
     pub publisher: Arc<dyn SocialMediaPublisherTrait>,
     pub templates: tera::Tera,
+    /// Where `static/` is served from, see [`assets`].
+    pub resources: PathBuf,
+    pub session: SessionConfig,
 }
 
 pub struct AppConfig {
     pub host: String,
     pub port: String,
     pub db_url: String,
+    /// Apply pending migrations before serving (`AUTO_MIGRATE=true`).
+    pub auto_migrate: bool,
+    /// Refuse to serve when the schema drifts from the entities (`SCHEMA_STRICT=true`).
+    pub schema_strict: bool,
+    /// How often scheduled articles are checked (`PUBLISH_INTERVAL_SECS`, 60 by default).
+    pub publish_interval: Duration,
+    /// How often the outbox is drained (`DISPATCH_INTERVAL_SECS`, 10 by default).
+    pub dispatch_interval: Duration,
+    /// Holds `templates/` and `static/` (`RESOURCES_DIR`, `resources` by default).
+    pub resources_dir: PathBuf,
+    /// Signs the session cookie (`SESSION_KEY`, at least 32 bytes), which is
+    /// HTTPS only with `SESSION_SECURE=true`.
+    pub session: SessionConfig,
+    /// Where the site is reachable, for links in announcements (`PUBLIC_URL`,
+    /// `http://HOST:PORT` by default).
+    pub public_url: String,
+    /// Where published articles are announced (`PUBLISHERS`), see [`publishers`].
+    pub publishers: Vec<PublisherSettings>,
 }
 impl AppConfig {
-    pub fn load() -> Result<AppConfig, std::io::Error> {
+    pub fn load() -> Result<AppConfig, io::Error> {
         dotenvy::dotenv().ok();
+        let host = env::var("HOST").unwrap_or("0.0.0.1".to_string());
+        let port = env::var("PORT").unwrap_or("8000".to_string());
+        let publishers =
+            PublisherSettings::parse(&env::var("PUBLISHERS").unwrap_or_default(), |key| {
+                env::var(key).ok()
+            })
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
         Ok(AppConfig {
-            host: env::var("HOST").unwrap_or("0.0.0.1".to_string()),
-            port: env::var("PORT").unwrap_or("8000".to_string()),
+            public_url: env::var("PUBLIC_URL").unwrap_or(format!("http://{host}:{port}")),
+            host,
+            port,
             db_url: env::var("DATABASE_URL").expect("db url is expected"),
+            auto_migrate: flag("AUTO_MIGRATE"),
+            schema_strict: flag("SCHEMA_STRICT"),
+            publish_interval: period("PUBLISH_INTERVAL_SECS", 60)?,
+            dispatch_interval: period("DISPATCH_INTERVAL_SECS", 10)?,
+            resources_dir: env::var("RESOURCES_DIR")
+                .unwrap_or("resources".to_string())
+                .into(),
+            session: session_config()?,
+            publishers,
         })
     }
 }
+
+fn session_config() -> Result<SessionConfig, io::Error> {
+    let Ok(secret) = env::var("SESSION_KEY") else {
+        tracing::warn!("SESSION_KEY is not set, signing sessions with a random key");
+        return Ok(SessionConfig::generate());
+    };
+    SessionConfig::from_secret(secret.as_bytes(), flag("SESSION_SECURE")).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "SESSION_KEY must be at least {} bytes",
+                session::MIN_KEY_LEN
+            ),
+        )
+    })
+}
+
+fn seconds(name: &str, default: u64) -> Duration {
+    Duration::from_secs(
+        env::var(name)
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(default),
+    )
+}
+
+/// The period of a background job, which can't be zero: the job's interval
+/// would panic and the job silently stop.
+fn period(name: &str, default: u64) -> Result<Duration, io::Error> {
+    match seconds(name, default) {
+        Duration::ZERO => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{name} must be at least 1"),
+        )),
+        period => Ok(period),
+    }
+}
+
+fn flag(name: &str) -> bool {
+    env::var(name)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{period, seconds};
+    use std::{env, time::Duration};
+
+    #[test]
+    fn seconds_fall_back_to_the_default() {
+        env::set_var("TEST_SECONDS_SET", "5");
+        env::set_var("TEST_SECONDS_GARBAGE", "soon");
+        assert_eq!(seconds("TEST_SECONDS_SET", 60), Duration::from_secs(5));
+        assert_eq!(seconds("TEST_SECONDS_GARBAGE", 60), Duration::from_secs(60));
+        assert_eq!(seconds("TEST_SECONDS_UNSET", 60), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn periods_are_not_zero() {
+        for name in ["TEST_PUBLISH_INTERVAL_SECS", "TEST_DISPATCH_INTERVAL_SECS"] {
+            env::set_var(name, "0");
+            let err = period(name, 10).unwrap_err();
+            assert_eq!(err.to_string(), format!("{name} must be at least 1"));
+        }
+        assert_eq!(
+            period("TEST_PERIOD_UNSET", 60).unwrap(),
+            Duration::from_secs(60)
+        );
+    }
+}
@@ -1,41 +1,130 @@
 use poem::listener::TcpListener;
 use poem::Server;
+use poem_article::migration::{self, Command};
+use poem_article::publishers::PublisherRegistry;
 use poem_article::repositories::DbRepository;
-use poem_article::services::{ArticleServiceSt, SocialMediaPublisher};
-use poem_article::{handlers, AppConfig, AppStateM};
-use sea_orm::Database;
-use tera::Tera;
+use poem_article::services::{
+    ArticleServiceSt, AuthorServiceSt, CommentServiceSt, OutboxServiceSt, PublicationServiceSt,
+    TagServiceSt, TokenServiceSt,
+};
+use poem_article::{assets, handlers, scheduler, schema, AppConfig, AppStateM};
+use sea_orm::{Database, DatabaseConnection};
 
 use crate::handlers::*;
+use std::env;
+use std::process::ExitCode;
 use std::sync::Arc;
-mod migration;
 
-// TODO : open API
+const USAGE: &str =
+    "usage: poem_article [--migrate | migrate [up|down|status|fresh] | check-schema]";
+
+enum Mode {
+    Serve,
+    Migrate(Command),
+    CheckSchema,
+}
+
+impl Mode {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+        let mode = match args.next().as_deref() {
+            None => Mode::Serve,
+            Some("--migrate") => Mode::Migrate(Command::Up),
+            Some("migrate") => Mode::Migrate(match args.next() {
+                Some(command) => command.parse()?,
+                None => Command::Up,
+            }),
+            Some("check-schema") => Mode::CheckSchema,
+            Some(other) => return Err(format!("unexpected argument `{other}`\n{USAGE}")),
+        };
+        match args.next() {
+            Some(extra) => Err(format!("unexpected argument `{extra}`\n{USAGE}")),
+            None => Ok(mode),
+        }
+    }
+}
 
 #[tokio::main]
-async fn start() -> std::io::Result<()> {
+async fn start() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
     //  TODO  :  let orm_metrics = sea_orm::metric::Info();
 
+    let mode = Mode::from_args(env::args().skip(1)).map_err(anyhow::Error::msg)?;
     let conf = AppConfig::load()?;
-    // migration::Migrator::up(conn, None).await.unwrap();
+    let conn = Database::connect(conf.db_url).await?;
+
+    match mode {
+        Mode::Migrate(command) => return migration::run(&conn, command).await.map_err(Into::into),
+        Mode::CheckSchema => {
+            let mismatches = schema::verify(&conn).await?;
+            for mismatch in &mismatches {
+                eprintln!("{mismatch}");
+            }
+            if !mismatches.is_empty() {
+                anyhow::bail!("{} schema mismatch(es) found", mismatches.len());
+            }
+            return Ok(());
+        }
+        Mode::Serve => {}
+    }
+    if conf.auto_migrate {
+        migration::apply_pending(&conn).await?;
+    }
+    check_schema(&conn, conf.schema_strict).await?;
 
-    let conn = Database::connect(conf.db_url).await.unwrap();
-    let repo = DbRepository::new(Arc::new(conn));
-    let service = ArticleServiceSt::new(Arc::new(repo));
+    let repo = Arc::new(DbRepository::new(Arc::new(conn)));
+    let publisher = Arc::new(PublisherRegistry::from_settings(
+        &conf.publishers,
+        &conf.public_url,
+    ));
+    let service = ArticleServiceSt::new(repo.clone());
+    let author_service = AuthorServiceSt::new(repo.clone());
+    let tag_service = TagServiceSt::new(repo.clone());
+    let comment_service = CommentServiceSt::new(repo.clone());
+    let token_service = TokenServiceSt::new(repo.clone());
+    let outbox_service = Arc::new(OutboxServiceSt::new(repo.clone(), publisher.clone()));
+    let publication_service = PublicationServiceSt::new(repo, publisher.clone());
+    let service = Arc::new(service);
+    scheduler::spawn(service.clone(), conf.publish_interval);
+    scheduler::spawn_dispatcher(outbox_service.clone(), conf.dispatch_interval);
     let app_state = AppStateM {
-        service: Arc::new(service),
-        publisher: Arc::new(SocialMediaPublisher {}),
-        templates: Tera::new("./templates/**/*").unwrap(),
+        service,
+        author_service: Arc::new(author_service),
+        tag_service: Arc::new(tag_service),
+        comment_service: Arc::new(comment_service),
+        token_service: Arc::new(token_service),
+        outbox_service,
+        publication_service: Arc::new(publication_service),
+        publisher,
+        templates: assets::templates(&conf.resources_dir)?,
+        resources: conf.resources_dir,
+        session: conf.session,
     };
     println!("{}:{}", conf.host, conf.port);
     Server::new(TcpListener::bind(format!("{}:{}", conf.host, conf.port)))
         .run(config_router(app_state))
         .await
+        .map_err(Into::into)
+}
+
+/// Logs every drift between the database and the entities, failing in strict mode.
+async fn check_schema(conn: &DatabaseConnection, strict: bool) -> anyhow::Result<()> {
+    let mismatches = schema::verify(conn).await?;
+    for mismatch in &mismatches {
+        tracing::warn!("schema drift: {mismatch}");
+    }
+    if strict && !mismatches.is_empty() {
+        anyhow::bail!("{} schema mismatch(es) found", mismatches.len());
+    }
+    Ok(())
 }
 
-fn main() {
-    if let Some(err) = start().err() {
-        println!("app error {err}")
+/// Errors go to stderr with a failing exit status, so scripts notice them.
+fn main() -> ExitCode {
+    match start() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("app error: {err:#}");
+            ExitCode::FAILURE
+        }
     }
 }
@@ -0,0 +1,135 @@
+//! Article content is CommonMark with GFM tables, strikethrough and
+//! footnotes, rendered to HTML that is safe to embed in a page.
+
+use ammonia::Builder;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use std::collections::HashSet;
+
+/// Footnote anchors are namespaced so they can't clobber the page's own ids.
+const FOOTNOTE_PREFIX: &str = "fn-";
+
+const TAGS: [&str; 31] = [
+    "a",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "div",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "img",
+    "input",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+/// Renders `markdown` and strips everything outside the allow-list, such as
+/// scripts, styles and event handler attributes.
+pub fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::FootnoteReference(label) => Event::FootnoteReference(footnote(label)),
+        Event::Start(Tag::FootnoteDefinition(label)) => {
+            Event::Start(Tag::FootnoteDefinition(footnote(label)))
+        }
+        Event::End(Tag::FootnoteDefinition(label)) => {
+            Event::End(Tag::FootnoteDefinition(footnote(label)))
+        }
+        event => event,
+    });
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+fn footnote(label: CowStr) -> CowStr {
+    format!("{FOOTNOTE_PREFIX}{label}").into()
+}
+
+fn sanitizer() -> Builder<'static> {
+    let mut builder = Builder::empty();
+    builder
+        .tags(HashSet::from(TAGS))
+        .generic_attributes(HashSet::from(["title"]))
+        .tag_attributes(
+            [
+                ("a", HashSet::from(["href"])),
+                ("img", HashSet::from(["src", "alt"])),
+                ("div", HashSet::from(["id"])),
+                ("input", HashSet::from(["type", "checked", "disabled"])),
+                ("ol", HashSet::from(["start"])),
+            ]
+            .into(),
+        )
+        .allowed_classes(
+            [
+                (
+                    "sup",
+                    HashSet::from(["footnote-reference", "footnote-definition-label"]),
+                ),
+                ("div", HashSet::from(["footnote-definition"])),
+            ]
+            .into(),
+        )
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .id_prefix(Some(FOOTNOTE_PREFIX))
+        .link_rel(Some("noopener noreferrer nofollow"));
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn tables() {
+        let html = render("| a | b |\n|---|---|\n| 1 | 2 |\n");
+        assert!(html.starts_with("<table><thead><tr><th>a</th><th>b</th></tr></thead>"));
+        assert!(html.contains("<td>1</td><td>2</td>"));
+    }
+
+    #[test]
+    fn footnotes() {
+        let html = render("Text[^note].\n\n[^note]: The note.\n");
+        assert!(html.contains(
+            r##"<sup class="footnote-reference"><a href="#fn-note" rel="noopener noreferrer nofollow">1</a></sup>"##
+        ));
+        assert!(html.contains(r#"<div class="footnote-definition" id="fn-note">"#));
+    }
+
+    #[test]
+    fn scripts_and_handlers_are_stripped() {
+        let html = render(
+            "<script>alert(1)</script>\n\n<img src=\"x.png\" onerror=\"alert(1)\">\n\n\
+             [link](javascript:alert(1)) <b style=\"color: red\">bold</b>",
+        );
+        assert!(!html.contains("script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript"));
+        assert!(!html.contains("style"));
+        assert!(html.contains(r#"<img src="x.png">"#));
+        assert!(html.contains("bold"));
+    }
+}
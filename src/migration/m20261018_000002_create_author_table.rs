@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Authors::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Authors::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Authors::FirstName).string().not_null())
+                    .col(ColumnDef::new(Authors::LastName).string().not_null())
+                    .col(
+                        ColumnDef::new(Authors::Email)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Authors::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Authors {
+    Table,
+    Id,
+    FirstName,
+    LastName,
+    Email,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite can't add a foreign key constraint through ALTER TABLE, but it
+        // accepts an inline REFERENCES clause on a nullable new column.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Articles::Table)
+                    .add_column(
+                        ColumnDef::new(Articles::AuthorId)
+                            .integer()
+                            .null()
+                            .extra("REFERENCES authors (id) ON DELETE SET NULL"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// SQLite refuses to drop a column a foreign key refers to, so the table
+    /// is rebuilt without it, as in `m20261018_000004`.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArticlesRebuild::Table)
+                    .col(
+                        ColumnDef::new(Articles::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Articles::Title).string().not_null())
+                    .col(ColumnDef::new(Articles::Text).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        let copy = Query::insert()
+            .into_table(ArticlesRebuild::Table)
+            .columns([Articles::Id, Articles::Title, Articles::Text])
+            .select_from(
+                Query::select()
+                    .columns([Articles::Id, Articles::Title, Articles::Text])
+                    .from(Articles::Table)
+                    .to_owned(),
+            )
+            .map_err(|e| DbErr::Migration(e.to_string()))?
+            .to_owned();
+        manager.exec_stmt(copy).await?;
+        manager
+            .drop_table(Table::drop().table(Articles::Table).to_owned())
+            .await?;
+        manager
+            .rename_table(
+                Table::rename()
+                    .table(ArticlesRebuild::Table, Articles::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Articles {
+    Table,
+    Id,
+    Title,
+    Text,
+    AuthorId,
+}
+
+#[derive(DeriveIden)]
+enum ArticlesRebuild {
+    #[sea_orm(iden = "articles_rebuild")]
+    Table,
+}
@@ -0,0 +1,108 @@
+use sea_orm_migration::prelude::*;
+
+/// The first migration created a non-null `text` column while the entity maps
+/// a nullable `content` column. SQLite can't change nullability in place, so
+/// the table is rebuilt and the data copied across.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild(manager, Articles::Content, true, Articles::Text).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild(manager, Articles::Text, false, Articles::Content).await
+    }
+}
+
+async fn rebuild(
+    manager: &SchemaManager<'_>,
+    body: Articles,
+    nullable: bool,
+    previous: Articles,
+) -> Result<(), DbErr> {
+    let mut body_col = ColumnDef::new(body);
+    body_col.string();
+    match nullable {
+        true => body_col.null(),
+        false => body_col.not_null(),
+    };
+    manager
+        .create_table(
+            Table::create()
+                .table(ArticlesRebuild::Table)
+                .col(
+                    ColumnDef::new(Articles::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Articles::Title).string().not_null())
+                .col(&mut body_col)
+                .col(ColumnDef::new(Articles::AuthorId).integer().null())
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(ArticlesRebuild::Table, Articles::AuthorId)
+                        .to(Authors::Table, Authors::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    let previous_expr: SimpleExpr = match nullable {
+        true => Expr::col(previous).into(),
+        false => Expr::col(previous).if_null(""),
+    };
+    let copy = Query::insert()
+        .into_table(ArticlesRebuild::Table)
+        .columns([Articles::Id, Articles::Title, body, Articles::AuthorId])
+        .select_from(
+            Query::select()
+                .column(Articles::Id)
+                .column(Articles::Title)
+                .expr(previous_expr)
+                .column(Articles::AuthorId)
+                .from(Articles::Table)
+                .to_owned(),
+        )
+        .map_err(|e| DbErr::Migration(e.to_string()))?
+        .to_owned();
+    manager.exec_stmt(copy).await?;
+
+    manager
+        .drop_table(Table::drop().table(Articles::Table).to_owned())
+        .await?;
+    manager
+        .rename_table(
+            Table::rename()
+                .table(ArticlesRebuild::Table, Articles::Table)
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden, Clone, Copy)]
+enum Articles {
+    Table,
+    Id,
+    Title,
+    Text,
+    Content,
+    AuthorId,
+}
+
+#[derive(DeriveIden)]
+enum ArticlesRebuild {
+    #[sea_orm(iden = "articles_rebuild")]
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Authors {
+    Table,
+    Id,
+}
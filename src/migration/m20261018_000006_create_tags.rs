@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tags::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Tags::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Tags::Slug).string().not_null().unique_key())
+                    .col(ColumnDef::new(Tags::Name).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArticleTags::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ArticleTags::ArticleId).integer().not_null())
+                    .col(ColumnDef::new(ArticleTags::TagId).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(ArticleTags::ArticleId)
+                            .col(ArticleTags::TagId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ArticleTags::Table, ArticleTags::ArticleId)
+                            .to(Articles::Table, Articles::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ArticleTags::Table, ArticleTags::TagId)
+                            .to(Tags::Table, Tags::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_article_tags_tag_id")
+                    .table(ArticleTags::Table)
+                    .col(ArticleTags::TagId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ArticleTags::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Tags::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    Id,
+    Slug,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum ArticleTags {
+    Table,
+    ArticleId,
+    TagId,
+}
+
+#[derive(DeriveIden)]
+enum Articles {
+    Table,
+    Id,
+}
@@ -0,0 +1,105 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Existing articles start their history with a first revision of their
+/// current state, attributed to their author.
+const BACKFILL_SQL: &str = r#"
+INSERT INTO article_revisions (article_id, rev, title, content, editor_id, created_at)
+SELECT id, 1, title, content, author_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+FROM articles
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArticleRevisions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArticleRevisions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ArticleRevisions::ArticleId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ArticleRevisions::Rev).integer().not_null())
+                    .col(ColumnDef::new(ArticleRevisions::Title).string().not_null())
+                    .col(ColumnDef::new(ArticleRevisions::Content).string().null())
+                    .col(ColumnDef::new(ArticleRevisions::EditorId).integer().null())
+                    .col(
+                        ColumnDef::new(ArticleRevisions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ArticleRevisions::Table, ArticleRevisions::ArticleId)
+                            .to(Articles::Table, Articles::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ArticleRevisions::Table, ArticleRevisions::EditorId)
+                            .to(Authors::Table, Authors::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_article_revisions_article_rev")
+                    .table(ArticleRevisions::Table)
+                    .col(ArticleRevisions::ArticleId)
+                    .col(ArticleRevisions::Rev)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared(BACKFILL_SQL)
+            .await
+            .map(|_| ())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ArticleRevisions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ArticleRevisions {
+    Table,
+    Id,
+    ArticleId,
+    Rev,
+    Title,
+    Content,
+    EditorId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Articles {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Authors {
+    Table,
+    Id,
+}
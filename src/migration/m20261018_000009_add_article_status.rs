@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every article was public before the workflow existed, so existing
+        // rows become published. SQLite adds one column per ALTER TABLE.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Articles::Table)
+                    .add_column(
+                        ColumnDef::new(Articles::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("published"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        for column in [Articles::PublishedAt, Articles::PublishAt] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Articles::Table)
+                        .add_column(ColumnDef::new(column).timestamp_with_time_zone().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE articles SET published_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')",
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_articles_status")
+                    .table(Articles::Table)
+                    .col(Articles::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_articles_status")
+                    .table(Articles::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [Articles::Status, Articles::PublishedAt, Articles::PublishAt] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Articles::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Articles {
+    Table,
+    Status,
+    PublishedAt,
+    PublishAt,
+}
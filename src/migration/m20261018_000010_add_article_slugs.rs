@@ -0,0 +1,172 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+use std::collections::HashSet;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Articles::Table)
+                    .add_column(
+                        ColumnDef::new(Articles::Slug)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        backfill(manager).await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_articles_slug")
+                    .table(Articles::Table)
+                    .col(Articles::Slug)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArticleSlugs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArticleSlugs::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ArticleSlugs::ArticleId).integer().not_null())
+                    .col(
+                        ColumnDef::new(ArticleSlugs::Slug)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ArticleSlugs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ArticleSlugs::Table, ArticleSlugs::ArticleId)
+                            .to(Articles::Table, Articles::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_article_slugs_article_id")
+                    .table(ArticleSlugs::Table)
+                    .col(ArticleSlugs::ArticleId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ArticleSlugs::Table).to_owned())
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_articles_slug")
+                    .table(Articles::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Articles::Table)
+                    .drop_column(Articles::Slug)
+                    .to_owned(),
+            )
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Gives existing articles a slug from their title, the oldest article
+/// winning on collisions.
+async fn backfill(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let conn = manager.get_connection();
+    let backend = manager.get_database_backend();
+    let rows = conn
+        .query_all(Statement::from_string(
+            backend,
+            "SELECT id, title FROM articles ORDER BY id",
+        ))
+        .await?;
+    let mut taken = HashSet::new();
+    for row in rows {
+        let id: i32 = row.try_get("", "id")?;
+        let title: String = row.try_get("", "title")?;
+        let base = for_title(&title);
+        let slug = (1..)
+            .map(|n| candidate(&base, n))
+            .find(|candidate| !taken.contains(candidate))
+            .expect("an unused candidate");
+        conn.execute(Statement::from_sql_and_values(
+            backend,
+            "UPDATE articles SET slug = ? WHERE id = ?",
+            [slug.clone().into(), id.into()],
+        ))
+        .await?;
+        taken.insert(slug);
+    }
+    Ok(())
+}
+
+// The slug rules as they were when this migration was written, so that
+// changes to `crate::slug` don't change what the migration does.
+
+fn for_title(title: &str) -> String {
+    let slug = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    match slug {
+        slug if slug.is_empty() => "article".to_string(),
+        slug => slug,
+    }
+}
+
+fn candidate(base: &str, n: u32) -> String {
+    match n {
+        0 | 1 => base.to_string(),
+        n => format!("{base}-{n}"),
+    }
+}
+
+#[derive(DeriveIden)]
+enum Articles {
+    Table,
+    Id,
+    Slug,
+}
+
+#[derive(DeriveIden)]
+enum ArticleSlugs {
+    Table,
+    Id,
+    ArticleId,
+    Slug,
+    CreatedAt,
+}
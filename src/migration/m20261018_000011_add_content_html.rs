@@ -0,0 +1,163 @@
+use ammonia::Builder;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+use std::collections::HashSet;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Both tables have an `id` and a Markdown `content`.
+const TABLES: [&str; 2] = ["articles", "article_revisions"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(ColumnDef::new(ContentHtml).text().null())
+                        .to_owned(),
+                )
+                .await?;
+            render_existing(manager, table).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(ContentHtml)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+async fn render_existing(manager: &SchemaManager<'_>, table: &str) -> Result<(), DbErr> {
+    let conn = manager.get_connection();
+    let backend = manager.get_database_backend();
+    let rows = conn
+        .query_all(Statement::from_string(
+            backend,
+            format!("SELECT id, content FROM {table} WHERE content IS NOT NULL"),
+        ))
+        .await?;
+    for row in rows {
+        let id: i32 = row.try_get("", "id")?;
+        let content: String = row.try_get("", "content")?;
+        conn.execute(Statement::from_sql_and_values(
+            backend,
+            format!("UPDATE {table} SET content_html = ? WHERE id = ?"),
+            [render(&content).into(), id.into()],
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+// The renderer as it was when this migration was written, so that changes
+// to `crate::markdown` don't change what the migration does.
+
+/// Footnote anchors are namespaced so they can't clobber the page's own ids.
+const FOOTNOTE_PREFIX: &str = "fn-";
+
+const TAGS: [&str; 31] = [
+    "a",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "div",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "img",
+    "input",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::FootnoteReference(label) => Event::FootnoteReference(footnote(label)),
+        Event::Start(Tag::FootnoteDefinition(label)) => {
+            Event::Start(Tag::FootnoteDefinition(footnote(label)))
+        }
+        Event::End(Tag::FootnoteDefinition(label)) => {
+            Event::End(Tag::FootnoteDefinition(footnote(label)))
+        }
+        event => event,
+    });
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+fn footnote(label: CowStr) -> CowStr {
+    format!("{FOOTNOTE_PREFIX}{label}").into()
+}
+
+fn sanitizer() -> Builder<'static> {
+    let mut builder = Builder::empty();
+    builder
+        .tags(HashSet::from(TAGS))
+        .generic_attributes(HashSet::from(["title"]))
+        .tag_attributes(
+            [
+                ("a", HashSet::from(["href"])),
+                ("img", HashSet::from(["src", "alt"])),
+                ("div", HashSet::from(["id"])),
+                ("input", HashSet::from(["type", "checked", "disabled"])),
+                ("ol", HashSet::from(["start"])),
+            ]
+            .into(),
+        )
+        .allowed_classes(
+            [
+                (
+                    "sup",
+                    HashSet::from(["footnote-reference", "footnote-definition-label"]),
+                ),
+                ("div", HashSet::from(["footnote-definition"])),
+            ]
+            .into(),
+        )
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .id_prefix(Some(FOOTNOTE_PREFIX))
+        .link_rel(Some("noopener noreferrer nofollow"));
+    builder
+}
+
+#[derive(DeriveIden)]
+struct ContentHtml;
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing authors keep writing; the first one becomes the admin, so
+        // someone can manage the others.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Authors::Table)
+                    .add_column(
+                        ColumnDef::new(Authors::Role)
+                            .string_len(16)
+                            .not_null()
+                            .default("author"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE authors SET role = 'admin' WHERE id = (SELECT min(id) FROM authors)",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Authors::Table)
+                    .drop_column(Authors::Role)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Authors {
+    Table,
+    Role,
+}
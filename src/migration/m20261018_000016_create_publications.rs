@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Publications::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Publications::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Publications::ArticleId).integer().not_null())
+                    .col(ColumnDef::new(Publications::Channel).string().not_null())
+                    .col(
+                        ColumnDef::new(Publications::State)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Publications::RemoteId).string())
+                    .col(ColumnDef::new(Publications::RemoteUrl).string())
+                    .col(ColumnDef::new(Publications::Attempts).integer().not_null())
+                    .col(ColumnDef::new(Publications::LastError).text())
+                    .col(ColumnDef::new(Publications::PostedAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(Publications::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Publications::Table, Publications::ArticleId)
+                            .to(Articles::Table, Articles::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // One row per article and channel.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_publications_article_channel")
+                    .table(Publications::Table)
+                    .col(Publications::ArticleId)
+                    .col(Publications::Channel)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Publications::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Publications {
+    Table,
+    Id,
+    ArticleId,
+    Channel,
+    State,
+    RemoteId,
+    RemoteUrl,
+    Attempts,
+    LastError,
+    PostedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Articles {
+    Table,
+    Id,
+}
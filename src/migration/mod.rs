@@ -1,12 +1,103 @@
 pub use sea_orm_migration::prelude::*;
 
+use sea_orm_migration::sea_orm::DatabaseConnection;
+use std::str::FromStr;
+
 mod m20230910_000001_create_article_table;
+mod m20261018_000002_create_author_table;
+mod m20261018_000003_add_article_author;
+mod m20261018_000004_rebuild_article_content;
+mod m20261018_000005_create_article_search;
+mod m20261018_000006_create_tags;
+mod m20261018_000007_create_comments;
+mod m20261018_000008_create_article_revisions;
+mod m20261018_000009_add_article_status;
+mod m20261018_000010_add_article_slugs;
+mod m20261018_000011_add_content_html;
+mod m20261018_000012_add_author_password;
+mod m20261018_000013_create_api_tokens;
+mod m20261018_000014_add_author_role;
+mod m20261018_000015_create_outbox;
+mod m20261018_000016_create_publications;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20230910_000001_create_article_table::Migration)]
+        vec![
+            Box::new(m20230910_000001_create_article_table::Migration),
+            Box::new(m20261018_000002_create_author_table::Migration),
+            Box::new(m20261018_000003_add_article_author::Migration),
+            Box::new(m20261018_000004_rebuild_article_content::Migration),
+            Box::new(m20261018_000005_create_article_search::Migration),
+            Box::new(m20261018_000006_create_tags::Migration),
+            Box::new(m20261018_000007_create_comments::Migration),
+            Box::new(m20261018_000008_create_article_revisions::Migration),
+            Box::new(m20261018_000009_add_article_status::Migration),
+            Box::new(m20261018_000010_add_article_slugs::Migration),
+            Box::new(m20261018_000011_add_content_html::Migration),
+            Box::new(m20261018_000012_add_author_password::Migration),
+            Box::new(m20261018_000013_create_api_tokens::Migration),
+            Box::new(m20261018_000014_add_author_role::Migration),
+            Box::new(m20261018_000015_create_outbox::Migration),
+            Box::new(m20261018_000016_create_publications::Migration),
+        ]
+    }
+}
+
+/// Sub-commands of `poem_article migrate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Apply all pending migrations.
+    Up,
+    /// Roll back the last applied migration.
+    Down,
+    /// Print every migration with its status.
+    Status,
+    /// Drop all tables and re-apply every migration.
+    Fresh,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Command::Up),
+            "down" => Ok(Command::Down),
+            "status" => Ok(Command::Status),
+            "fresh" => Ok(Command::Fresh),
+            other => Err(format!(
+                "unknown migrate command `{other}`, expected up|down|status|fresh"
+            )),
+        }
+    }
+}
+
+pub async fn run(conn: &DatabaseConnection, command: Command) -> Result<(), DbErr> {
+    match command {
+        Command::Up => apply_pending(conn).await,
+        Command::Down => Migrator::down(conn, Some(1)).await,
+        Command::Status => {
+            for migration in Migrator::get_migration_with_status(conn).await? {
+                println!("{}\t{}", migration.status(), migration.name());
+            }
+            Ok(())
+        }
+        Command::Fresh => Migrator::fresh(conn).await,
+    }
+}
+
+/// Logs the pending migrations, then applies them.
+pub async fn apply_pending(conn: &DatabaseConnection) -> Result<(), DbErr> {
+    let pending = Migrator::get_pending_migrations(conn).await?;
+    if pending.is_empty() {
+        tracing::info!("database schema is up to date");
+        return Ok(());
+    }
+    for migration in &pending {
+        tracing::info!("pending migration {}", migration.name());
     }
+    Migrator::up(conn, None).await
 }
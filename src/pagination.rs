@@ -0,0 +1,223 @@
+//! Page requests and the page envelope returned by list queries.
+//!
+//! Lists are either numbered pages ([`Params`], [`Page`]) or keyset slices
+//! that continue after a [`Cursor`] ([`KeysetParams`], [`Slice`]).
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::error::{Error, Result};
+
+pub const DEFAULT_PAGE_SIZE: u64 = 10;
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+fn clamp_size(size: Option<i64>) -> u64 {
+    size.map_or(DEFAULT_PAGE_SIZE, |size| {
+        size.clamp(1, MAX_PAGE_SIZE as i64) as u64
+    })
+}
+
+/// A validated page request. Pages are numbered from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    page: u64,
+    page_size: u64,
+}
+
+impl Params {
+    /// Out of range values are clamped instead of rejected: pages below 1
+    /// become 1 and the page size is kept within `1..=MAX_PAGE_SIZE`.
+    pub fn new(page: Option<i64>, page_size: Option<i64>) -> Self {
+        Self {
+            page: page.unwrap_or(1).max(1) as u64,
+            page_size: page_size.map_or(DEFAULT_PAGE_SIZE, |size| {
+                size.clamp(1, MAX_PAGE_SIZE as i64) as u64
+            }),
+        }
+    }
+
+    pub fn page(&self) -> u64 {
+        self.page
+    }
+
+    pub fn page_size(&self) -> u64 {
+        self.page_size
+    }
+
+    /// Zero-based page index, as expected by SeaORM's `Paginator`.
+    pub fn index(&self) -> u64 {
+        self.page - 1
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub page_size: u64,
+    pub total_items: u64,
+    pub total_pages: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, params: Params, total_items: u64) -> Self {
+        Self {
+            items,
+            page: params.page,
+            page_size: params.page_size,
+            total_items,
+            total_pages: total_items.div_ceil(params.page_size),
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            page: self.page,
+            page_size: self.page_size,
+            total_items: self.total_items,
+            total_pages: self.total_pages,
+        }
+    }
+
+    pub fn next_page(&self) -> Option<u64> {
+        (self.page < self.total_pages).then_some(self.page + 1)
+    }
+
+    /// A page past the end points back to the last page.
+    pub fn prev_page(&self) -> Option<u64> {
+        (self.page > 1).then(|| (self.page - 1).min(self.total_pages.max(1)))
+    }
+}
+
+/// Key of the last row a client has seen. Clients get it as an opaque token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor(pub i32);
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.0.to_string())
+    }
+
+    pub fn decode(token: &str) -> Result<Self> {
+        URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|key| key.parse().ok())
+            .map(Cursor)
+            .ok_or_else(|| Error::Validation(format!("`{token}` is not a valid cursor")))
+    }
+}
+
+/// A keyset request: up to `limit` rows ordered by key, after `after`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeysetParams {
+    after: Option<Cursor>,
+    limit: u64,
+}
+
+impl KeysetParams {
+    /// The limit is clamped like [`Params`] page sizes.
+    pub fn new(after: Option<Cursor>, limit: Option<i64>) -> Self {
+        Self {
+            after,
+            limit: clamp_size(limit),
+        }
+    }
+
+    pub fn after(&self) -> Option<Cursor> {
+        self.after
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slice<T> {
+    pub items: Vec<T>,
+    pub limit: u64,
+    /// `None` once the last row has been returned.
+    pub next: Option<Cursor>,
+}
+
+impl<T> Slice<T> {
+    /// Expects up to `limit + 1` rows; the extra row only tells that more follow.
+    pub fn new(mut items: Vec<T>, params: KeysetParams, key: impl Fn(&T) -> i32) -> Self {
+        let limit = params.limit;
+        let next = match items.len() as u64 > limit {
+            true => {
+                items.truncate(limit as usize);
+                items.last().map(|last| Cursor(key(last)))
+            }
+            false => None,
+        };
+        Self { items, limit, next }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Slice<U> {
+        Slice {
+            items: self.items.into_iter().map(f).collect(),
+            limit: self.limit,
+            next: self.next,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cursor, KeysetParams, Page, Params, Slice, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+
+    #[test]
+    fn params_are_clamped() {
+        assert_eq!(Params::new(None, None), Params::default());
+        let params = Params::new(Some(-3), Some(0));
+        assert_eq!((params.page(), params.page_size()), (1, 1));
+        let params = Params::new(Some(2), Some(10_000));
+        assert_eq!((params.page(), params.page_size()), (2, MAX_PAGE_SIZE));
+        assert_eq!(Params::new(Some(1), None).page_size(), DEFAULT_PAGE_SIZE);
+    }
+
+    #[test]
+    fn page_navigation() {
+        let page = Page::new(vec![(); 10], Params::new(Some(1), Some(10)), 25);
+        assert_eq!(page.total_pages, 3);
+        assert_eq!((page.prev_page(), page.next_page()), (None, Some(2)));
+
+        let page = Page::new(vec![(); 5], Params::new(Some(3), Some(10)), 25);
+        assert_eq!((page.prev_page(), page.next_page()), (Some(2), None));
+
+        let page = Page::<()>::new(vec![], Params::new(Some(9), Some(10)), 25);
+        assert_eq!((page.prev_page(), page.next_page()), (Some(3), None));
+
+        let page = Page::<()>::new(vec![], Params::default(), 0);
+        assert_eq!(page.total_pages, 0);
+        assert_eq!((page.prev_page(), page.next_page()), (None, None));
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let token = Cursor(42).encode();
+        assert_eq!(Cursor::decode(&token).unwrap(), Cursor(42));
+        assert!(Cursor::decode("not a cursor").is_err());
+        assert!(Cursor::decode(&Cursor(7).encode()[1..]).is_err());
+    }
+
+    #[test]
+    fn slice_has_next_only_when_more_rows_follow() {
+        let params = KeysetParams::new(None, Some(2));
+        let slice = Slice::new(vec![1, 2, 3], params, |id| *id);
+        assert_eq!((slice.items, slice.next), (vec![1, 2], Some(Cursor(2))));
+
+        let slice = Slice::new(vec![1, 2], params, |id| *id);
+        assert_eq!((slice.items, slice.next), (vec![1, 2], None));
+    }
+}
@@ -0,0 +1,238 @@
+//! Who may do what. Services ask [`authorize`] before changing anything, and
+//! a denial is an [`Error::Forbidden`].
+
+use sea_orm::ActiveEnum;
+use std::fmt;
+
+use crate::domain::article;
+use crate::domain::author::{self, Role};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Action<'a> {
+    /// Writing a new article on behalf of `author_id`.
+    CreateArticle { author_id: Option<i32> },
+    /// Changing the text or tags, moving between draft and review, and deleting.
+    EditArticle(&'a article::Model),
+    /// Publishing, scheduling, archiving, or taking back a published article.
+    PublishArticle(&'a article::Model),
+    /// Editing and deleting the comments on an article.
+    ModerateComments(&'a article::Model),
+    /// Changing the name, email or password of an author.
+    EditAuthor(i32),
+    /// Creating and deleting authors, and giving them roles.
+    ManageAuthors,
+    /// Inspecting and replaying the events in the outbox.
+    ManageOutbox,
+}
+
+impl<'a> Action<'a> {
+    /// What moving `article` to the `to` status amounts to.
+    pub fn transition(article: &'a article::Model, to: article::Status) -> Self {
+        use article::Status::*;
+        match (article.status, to) {
+            (Draft | InReview, Draft | InReview) => Action::EditArticle(article),
+            _ => Action::PublishArticle(article),
+        }
+    }
+}
+
+impl fmt::Display for Action<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::CreateArticle { .. } => write!(f, "write articles for others"),
+            Action::EditArticle(article) => write!(f, "edit article {}", article.id),
+            Action::PublishArticle(article) => write!(f, "publish article {}", article.id),
+            Action::ModerateComments(article) => {
+                write!(f, "moderate the comments on article {}", article.id)
+            }
+            Action::EditAuthor(id) => write!(f, "edit author {id}"),
+            Action::ManageAuthors => write!(f, "manage authors"),
+            Action::ManageOutbox => write!(f, "manage the outbox"),
+        }
+    }
+}
+
+/// Admins may do anything and editors anything with articles. Authors write
+/// and edit their own articles and moderate the comments on them, but don't
+/// publish; readers only read. Anyone may edit their own profile.
+pub fn allows(actor: &author::Model, action: Action) -> bool {
+    use Role::*;
+    match (actor.role, action) {
+        (Admin, _) => true,
+        (Editor, Action::CreateArticle { .. })
+        | (Editor, Action::EditArticle(_))
+        | (Editor, Action::PublishArticle(_))
+        | (Editor, Action::ModerateComments(_)) => true,
+        (Author, Action::CreateArticle { author_id }) => author_id == Some(actor.id),
+        (Author, Action::EditArticle(article)) | (Author, Action::ModerateComments(article)) => {
+            article.author_id == Some(actor.id)
+        }
+        (_, Action::EditAuthor(id)) => id == actor.id,
+        _ => false,
+    }
+}
+
+/// Articles that aren't published are only seen by those who may edit them.
+pub fn may_read(viewer: Option<&author::Model>, article: &article::Model) -> bool {
+    article.status == article::Status::Published
+        || viewer.is_some_and(|actor| allows(actor, Action::EditArticle(article)))
+}
+
+/// Whose unpublished articles `viewer` may list: everyone's (`None`), or
+/// only their own.
+pub fn unpublished_scope(viewer: Option<&author::Model>) -> Result<Option<i32>> {
+    let actor = viewer.ok_or(Error::Unauthorized)?;
+    match actor.role {
+        Role::Admin | Role::Editor => Ok(None),
+        Role::Author => Ok(Some(actor.id)),
+        Role::Reader => Err(Error::Forbidden(format!(
+            "the `{}` role may not list unpublished articles",
+            actor.role.to_value()
+        ))),
+    }
+}
+
+pub fn authorize(actor: &author::Model, action: Action) -> Result<()> {
+    match allows(actor, action) {
+        true => Ok(()),
+        false => Err(Error::Forbidden(match action {
+            Action::CreateArticle { .. } if actor.role == Role::Reader => {
+                format!(
+                    "the `{}` role may not write articles",
+                    actor.role.to_value()
+                )
+            }
+            _ => format!("the `{}` role may not {action}", actor.role.to_value()),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::tests::draft;
+
+    fn as_role(id: i32, role: Role) -> author::Model {
+        author::Model {
+            id,
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            password_hash: None,
+            role,
+        }
+    }
+
+    fn by(author_id: i32, status: article::Status) -> article::Model {
+        article::Model {
+            status,
+            ..draft(1, Some(author_id))
+        }
+    }
+
+    #[test]
+    fn rules_by_role() {
+        use article::Status::*;
+        let own = by(1, Draft);
+        let other = by(2, Draft);
+        // What author 1 may do in each role, in the order of the actions.
+        let actions = [
+            Action::CreateArticle { author_id: Some(1) },
+            Action::CreateArticle { author_id: Some(2) },
+            Action::EditArticle(&own),
+            Action::EditArticle(&other),
+            Action::PublishArticle(&own),
+            Action::PublishArticle(&other),
+            Action::ModerateComments(&own),
+            Action::ModerateComments(&other),
+            Action::EditAuthor(1),
+            Action::EditAuthor(2),
+            Action::ManageAuthors,
+            Action::ManageOutbox,
+        ];
+        for (role, expected) in [
+            (Role::Admin, [true; 12]),
+            (
+                Role::Editor,
+                [
+                    true, true, true, true, true, true, true, true, true, false, false, false,
+                ],
+            ),
+            (
+                Role::Author,
+                [
+                    true, false, true, false, false, false, true, false, true, false, false, false,
+                ],
+            ),
+            (
+                Role::Reader,
+                [
+                    false, false, false, false, false, false, false, false, true, false, false,
+                    false,
+                ],
+            ),
+        ] {
+            let actor = as_role(1, role);
+            let allowed = actions.map(|action| allows(&actor, action));
+            assert_eq!(allowed, expected, "{role:?}");
+        }
+    }
+
+    #[test]
+    fn unpublished_articles_are_hidden() {
+        use article::Status::*;
+        let draft = by(1, Draft);
+        assert!(may_read(None, &by(1, Published)));
+        assert!(!may_read(None, &draft));
+        assert!(may_read(Some(&as_role(1, Role::Author)), &draft));
+        assert!(!may_read(Some(&as_role(2, Role::Author)), &draft));
+        assert!(may_read(Some(&as_role(2, Role::Editor)), &draft));
+
+        assert!(matches!(unpublished_scope(None), Err(Error::Unauthorized)));
+        assert!(matches!(
+            unpublished_scope(Some(&as_role(1, Role::Reader))),
+            Err(Error::Forbidden(_))
+        ));
+        assert_eq!(
+            unpublished_scope(Some(&as_role(1, Role::Author))).ok(),
+            Some(Some(1))
+        );
+        assert_eq!(
+            unpublished_scope(Some(&as_role(1, Role::Admin))).ok(),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn publishing_transitions() {
+        use article::Status::*;
+        let draft = by(1, Draft);
+        assert!(matches!(
+            Action::transition(&draft, InReview),
+            Action::EditArticle(_)
+        ));
+        assert!(matches!(
+            Action::transition(&draft, Published),
+            Action::PublishArticle(_)
+        ));
+        let published = by(1, Published);
+        assert!(matches!(
+            Action::transition(&published, Archived),
+            Action::PublishArticle(_)
+        ));
+    }
+
+    #[test]
+    fn denials_are_forbidden() {
+        let article = by(2, article::Status::Draft);
+        let result = authorize(&as_role(1, Role::Author), Action::EditArticle(&article));
+        match result {
+            Err(Error::Forbidden(message)) => {
+                assert_eq!(message, "the `author` role may not edit article 1")
+            }
+            other => panic!("expected a denial, got {other:?}"),
+        }
+        assert!(authorize(&as_role(1, Role::Editor), Action::EditArticle(&article)).is_ok());
+    }
+}
@@ -0,0 +1,449 @@
+//! Announces published articles elsewhere. Each backend is a channel,
+//! configured with `PUBLISHERS`, a comma separated list of names, and for
+//! each name `N`:
+//!
+//! - `PUBLISHER_N_KIND`: `webhook` or `mastodon`
+//! - `PUBLISHER_N_URL`: where the webhook posts to, or the Mastodon instance
+//! - `PUBLISHER_N_TOKEN`: the bearer token, required for Mastodon
+//! - `PUBLISHER_N_VISIBILITY`: of Mastodon statuses, `public` by default
+
+use chrono::{DateTime, Utc};
+use poem::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::domain::article;
+use crate::error::{Error, Result};
+use crate::services::{Posted, SocialMediaPublisherTrait};
+
+/// Requests to other sites give up after this long.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Shown instead of tokens, so that debug output doesn't leak them.
+const REDACTED: &str = "<redacted>";
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum PublisherKind {
+    Webhook {
+        url: String,
+        token: Option<String>,
+    },
+    Mastodon {
+        url: String,
+        token: String,
+        visibility: String,
+    },
+}
+
+impl fmt::Debug for PublisherKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublisherKind::Webhook { url, token } => f
+                .debug_struct("Webhook")
+                .field("url", url)
+                .field("token", &token.as_ref().map(|_| REDACTED))
+                .finish(),
+            PublisherKind::Mastodon {
+                url,
+                token: _,
+                visibility,
+            } => f
+                .debug_struct("Mastodon")
+                .field("url", url)
+                .field("token", &REDACTED)
+                .field("visibility", visibility)
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublisherSettings {
+    pub name: String,
+    pub kind: PublisherKind,
+}
+
+impl PublisherSettings {
+    /// Reads the backends listed in `names` with `var`, which looks up the
+    /// settings described above.
+    pub fn parse(
+        names: &str,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Vec<PublisherSettings>, String> {
+        let mut settings: Vec<PublisherSettings> = vec![];
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if settings.iter().any(|s| s.name == name) {
+                return Err(format!("publisher `{name}` is listed twice"));
+            }
+            let prefix = format!("PUBLISHER_{}", name.to_uppercase().replace('-', "_"));
+            let get = |key: &str| var(&format!("{prefix}_{key}")).filter(|v| !v.is_empty());
+            let require = |key: &str| get(key).ok_or(format!("{prefix}_{key} is not set"));
+            let kind = match require("KIND")?.as_str() {
+                "webhook" => PublisherKind::Webhook {
+                    url: require("URL")?,
+                    token: get("TOKEN"),
+                },
+                "mastodon" => PublisherKind::Mastodon {
+                    url: require("URL")?,
+                    token: require("TOKEN")?,
+                    visibility: get("VISIBILITY").unwrap_or("public".to_string()),
+                },
+                other => return Err(format!("{prefix}_KIND `{other}` is not a known publisher")),
+            };
+            settings.push(PublisherSettings {
+                name: name.to_string(),
+                kind,
+            });
+        }
+        Ok(settings)
+    }
+}
+
+/// What the publishers tell about an article.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArticlePayload {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    /// The absolute permalink.
+    pub url: String,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl ArticlePayload {
+    pub fn new(article: &article::Model, site_url: &str) -> Self {
+        let path = article
+            .permalink()
+            .unwrap_or_else(|| format!("/articles/{}", article.id));
+        Self {
+            id: article.id,
+            title: article.title.clone(),
+            slug: article.slug.clone(),
+            url: format!("{}{path}", site_url.trim_end_matches('/')),
+            published_at: article.published_at,
+        }
+    }
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .user_agent(concat!("poem_article/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("the HTTP client builds")
+}
+
+fn failed(name: &str, e: impl fmt::Display) -> Error {
+    Error::Internal(anyhow::anyhow!("publishing to `{name}` failed: {e}"))
+}
+
+/// What a channel may answer with.
+#[derive(Default, Deserialize)]
+struct Remote {
+    id: Option<serde_json::Value>,
+    url: Option<String>,
+}
+
+async fn posted(name: &str, sent: reqwest::Result<reqwest::Response>) -> Result<Posted> {
+    let resp = sent
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| failed(name, e))?;
+    // Webhooks need not answer with JSON.
+    let remote: Remote = resp.json().await.unwrap_or_default();
+    Ok(Posted {
+        remote_id: remote.id.map(|id| match id {
+            serde_json::Value::String(id) => id,
+            other => other.to_string(),
+        }),
+        remote_url: remote.url,
+    })
+}
+
+/// POSTs `{"event": "article.published", "article": {...}}` to a URL.
+#[derive(Clone)]
+pub struct Webhook {
+    name: String,
+    url: String,
+    token: Option<String>,
+    site_url: String,
+    client: reqwest::Client,
+}
+
+impl Webhook {
+    pub fn new(name: &str, url: &str, token: Option<String>, site_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            token,
+            site_url: site_url.to_string(),
+            client: client(),
+        }
+    }
+}
+
+impl fmt::Debug for Webhook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Webhook")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("token", &self.token.as_ref().map(|_| REDACTED))
+            .field("site_url", &self.site_url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookEvent<'a> {
+    event: &'a str,
+    article: ArticlePayload,
+}
+
+#[async_trait]
+impl SocialMediaPublisherTrait for Webhook {
+    fn channels(&self) -> Vec<String> {
+        vec![self.name.clone()]
+    }
+
+    /// Answering with `{"id", "url"}` is optional.
+    async fn publish_to(&self, _channel: &str, article: &article::Model) -> Result<Posted> {
+        let event = WebhookEvent {
+            event: "article.published",
+            article: ArticlePayload::new(article, &self.site_url),
+        };
+        let mut req = self.client.post(&self.url).json(&event);
+        if let Some(token) = &self.token {
+            req = req.bearer_auth(token);
+        }
+        posted(&self.name, req.send().await).await
+    }
+}
+
+/// Posts a status with the title and permalink through the Mastodon API,
+/// which Pleroma, GoToSocial and others speak as well.
+#[derive(Clone)]
+pub struct Mastodon {
+    name: String,
+    url: String,
+    token: String,
+    visibility: String,
+    site_url: String,
+    client: reqwest::Client,
+}
+
+impl Mastodon {
+    pub fn new(name: &str, url: &str, token: &str, visibility: &str, site_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            visibility: visibility.to_string(),
+            site_url: site_url.to_string(),
+            client: client(),
+        }
+    }
+}
+
+impl fmt::Debug for Mastodon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mastodon")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("token", &REDACTED)
+            .field("visibility", &self.visibility)
+            .field("site_url", &self.site_url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize)]
+struct Status<'a> {
+    status: String,
+    visibility: &'a str,
+}
+
+#[async_trait]
+impl SocialMediaPublisherTrait for Mastodon {
+    fn channels(&self) -> Vec<String> {
+        vec![self.name.clone()]
+    }
+
+    async fn publish_to(&self, _channel: &str, article: &article::Model) -> Result<Posted> {
+        let payload = ArticlePayload::new(article, &self.site_url);
+        let status = Status {
+            status: format!("{}\n\n{}", payload.title, payload.url),
+            visibility: &self.visibility,
+        };
+        let sent = self
+            .client
+            .post(format!("{}/api/v1/statuses", self.url))
+            .bearer_auth(&self.token)
+            .json(&status)
+            .send()
+            .await;
+        posted(&self.name, sent).await
+    }
+}
+
+/// The configured publishers by name, each a channel of the registry.
+#[derive(Debug, Default, Clone)]
+pub struct PublisherRegistry {
+    publishers: Vec<(String, Arc<dyn SocialMediaPublisherTrait>)>,
+}
+
+impl PublisherRegistry {
+    /// `site_url` makes the permalinks absolute.
+    pub fn from_settings(settings: &[PublisherSettings], site_url: &str) -> Self {
+        let mut registry = PublisherRegistry::default();
+        for s in settings {
+            let publisher: Arc<dyn SocialMediaPublisherTrait> = match &s.kind {
+                PublisherKind::Webhook { url, token } => {
+                    Arc::new(Webhook::new(&s.name, url, token.clone(), site_url))
+                }
+                PublisherKind::Mastodon {
+                    url,
+                    token,
+                    visibility,
+                } => Arc::new(Mastodon::new(&s.name, url, token, visibility, site_url)),
+            };
+            registry.register(&s.name, publisher);
+        }
+        registry
+    }
+
+    /// Replaces a publisher of the same name.
+    pub fn register(&mut self, name: &str, publisher: Arc<dyn SocialMediaPublisherTrait>) {
+        self.publishers.retain(|(n, _)| n != name);
+        self.publishers.push((name.to_string(), publisher));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn SocialMediaPublisherTrait>> {
+        self.publishers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, p)| p.clone())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.publishers.iter().map(|(n, _)| n.as_str())
+    }
+}
+
+#[async_trait]
+impl SocialMediaPublisherTrait for PublisherRegistry {
+    fn channels(&self) -> Vec<String> {
+        self.names().map(str::to_string).collect()
+    }
+
+    async fn publish_to(&self, channel: &str, article: &article::Model) -> Result<Posted> {
+        let publisher = self.get(channel).ok_or(Error::NotFound("channel"))?;
+        publisher.publish_to(channel, article).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::tests::draft;
+    use std::collections::HashMap;
+
+    fn parse(names: &str, vars: &[(&str, &str)]) -> Result<Vec<PublisherSettings>, String> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        PublisherSettings::parse(names, |key| vars.get(key).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn settings_from_variables() {
+        let settings = parse(
+            "hook, social-club",
+            &[
+                ("PUBLISHER_HOOK_KIND", "webhook"),
+                ("PUBLISHER_HOOK_URL", "http://localhost/hook"),
+                ("PUBLISHER_SOCIAL_CLUB_KIND", "mastodon"),
+                ("PUBLISHER_SOCIAL_CLUB_URL", "https://social.example"),
+                ("PUBLISHER_SOCIAL_CLUB_TOKEN", "secret"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            settings,
+            vec![
+                PublisherSettings {
+                    name: "hook".to_string(),
+                    kind: PublisherKind::Webhook {
+                        url: "http://localhost/hook".to_string(),
+                        token: None,
+                    },
+                },
+                PublisherSettings {
+                    name: "social-club".to_string(),
+                    kind: PublisherKind::Mastodon {
+                        url: "https://social.example".to_string(),
+                        token: "secret".to_string(),
+                        visibility: "public".to_string(),
+                    },
+                },
+            ]
+        );
+        assert_eq!(parse("", &[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn bad_settings_are_rejected() {
+        for (names, vars, message) in [
+            ("hook", &[][..], "PUBLISHER_HOOK_KIND is not set"),
+            (
+                "hook",
+                &[("PUBLISHER_HOOK_KIND", "fax")][..],
+                "PUBLISHER_HOOK_KIND `fax` is not a known publisher",
+            ),
+            (
+                "masto",
+                &[
+                    ("PUBLISHER_MASTO_KIND", "mastodon"),
+                    ("PUBLISHER_MASTO_URL", "https://social.example"),
+                ][..],
+                "PUBLISHER_MASTO_TOKEN is not set",
+            ),
+        ] {
+            assert_eq!(parse(names, vars), Err(message.to_string()));
+        }
+    }
+
+    #[test]
+    fn tokens_are_redacted() {
+        let settings = parse(
+            "hook, social",
+            &[
+                ("PUBLISHER_HOOK_KIND", "webhook"),
+                ("PUBLISHER_HOOK_URL", "http://localhost/hook"),
+                ("PUBLISHER_HOOK_TOKEN", "hook-secret"),
+                ("PUBLISHER_SOCIAL_KIND", "mastodon"),
+                ("PUBLISHER_SOCIAL_URL", "https://social.example"),
+                ("PUBLISHER_SOCIAL_TOKEN", "social-secret"),
+            ],
+        )
+        .unwrap();
+        let registry = PublisherRegistry::from_settings(&settings, "https://blog.example");
+        for debug in [format!("{settings:?}"), format!("{registry:?}")] {
+            assert!(!debug.contains("secret"), "{debug}");
+            assert!(debug.contains("https://social.example"), "{debug}");
+            assert_eq!(debug.matches(REDACTED).count(), 2, "{debug}");
+        }
+    }
+
+    #[test]
+    fn payload_links_the_permalink() {
+        let published_at = "2026-10-18T12:00:00Z".parse().unwrap();
+        let article = article::Model {
+            title: "Hello".to_string(),
+            slug: "hello".to_string(),
+            status: article::Status::Published,
+            published_at: Some(published_at),
+            ..draft(1, None)
+        };
+        let payload = ArticlePayload::new(&article, "https://blog.example/");
+        assert_eq!(payload.url, "https://blog.example/posts/2026/hello");
+    }
+}
@@ -1,19 +1,117 @@
-use anyhow::Result;
 use sea_orm::entity::prelude::*;
-use sea_orm::{DatabaseConnection, QueryOrder, Set};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    DatabaseConnection, DbBackend, FromQueryResult, NotSet, QueryOrder, QuerySelect, Set,
+    Statement, TransactionTrait, Unchanged,
+};
 use std::sync::Arc;
 
+use crate::auth;
 use crate::domain::*;
+use crate::error::Result;
+use crate::markdown;
+use crate::pagination::{KeysetParams, Page, Params, Slice};
+use crate::search::{self, SearchQuery};
+use crate::slug;
 use async_trait::async_trait;
 
 pub struct ArticleCreate {
     pub title: String,
+    pub content: Option<String>,
+    pub author_id: Option<i32>,
 }
 
+/// Fields left as `None` are not sent to the database.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ArticleUpdate {
+    pub title: Option<String>,
+    pub content: Option<Option<String>>,
+    /// Recorded on the revision written for the update.
+    pub editor_id: Option<i32>,
+}
+
+#[derive(Clone)]
 pub struct AuthorCreate {
-    first_name: String,
-    last_name: String,
-    email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    /// Only its hash is stored.
+    pub password: Option<String>,
+    pub role: author::Role,
+}
+
+/// Fields left as `None` are not sent to the database.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AuthorUpdate {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    /// Only its hash is stored.
+    pub password: Option<String>,
+    pub role: Option<author::Role>,
+}
+
+pub struct CommentCreate {
+    pub article_id: i32,
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    pub body: String,
+}
+
+pub struct TokenCreate {
+    pub author_id: i32,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<api_token::Scope>,
+    pub expires_at: DateTimeUtc,
+}
+
+/// Sets the workflow fields of an article as a whole.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusUpdate {
+    pub status: article::Status,
+    pub published_at: Option<DateTimeUtc>,
+    pub publish_at: Option<DateTimeUtc>,
+}
+
+/// Sets the delivery fields of an outbox entry as a whole.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxUpdate {
+    pub state: outbox::State,
+    pub attempts: i32,
+    pub next_attempt_at: DateTimeUtc,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTimeUtc>,
+}
+
+/// One try at posting an article to a channel; `error` is set when it failed.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PublicationAttempt {
+    pub article_id: i32,
+    pub channel: String,
+    pub remote_id: Option<String>,
+    pub remote_url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Narrows article listings; the default lists every article.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ArticleFilter {
+    /// Only articles carrying the tag with this slug.
+    pub tag: Option<String>,
+    pub status: Option<article::Status>,
+    /// Only the articles of this author.
+    pub author_id: Option<i32>,
+}
+
+impl ArticleFilter {
+    /// What public listings show.
+    pub fn published() -> Self {
+        Self {
+            status: Some(article::Status::Published),
+            ..Default::default()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -23,22 +121,288 @@ use mockall::{automock, predicate::*};
 pub trait ArticleRepositoryTrait: Sync + Send {
     async fn create(&self, f: &ArticleCreate) -> Result<article::ActiveModel>;
     async fn find_by_id(&self, id: i32) -> Result<Option<article::Model>>;
-    async fn find_pages(&self, page: i32, page_size: i32) -> Result<Vec<article::Model>>;
+    /// Also matches old slugs, in which case the returned article has a
+    /// different `slug` than the one asked for.
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<article::Model>>;
+    async fn find_pages(
+        &self,
+        filter: &ArticleFilter,
+        params: Params,
+    ) -> Result<Page<article::Model>>;
+    /// Keyset listing ordered by id, continuing after `params.after()`.
+    async fn find_after(
+        &self,
+        filter: &ArticleFilter,
+        params: KeysetParams,
+    ) -> Result<Slice<article::Model>>;
+    async fn update(&self, id: i32, f: &ArticleUpdate) -> Result<Option<article::Model>>;
+    /// Unlike [`ArticleRepositoryTrait::update`], this writes no revision.
+    /// Queues an [`outbox::Event::ArticlePublished`] when the article becomes published.
+    async fn update_status(&self, id: i32, f: &StatusUpdate) -> Result<Option<article::Model>>;
+    /// Publishes the drafts and reviewed articles scheduled at or before
+    /// `now`, returning them, and queues an event for each.
+    async fn publish_due(&self, now: DateTimeUtc) -> Result<Vec<article::Model>>;
+    async fn delete(&self, id: i32) -> Result<bool>;
+    async fn find_with_author(&self, id: i32) -> Result<Option<article::WithAuthor>>;
+    /// `None` when the author does not exist, otherwise all of its articles.
+    async fn find_by_author(&self, author_id: i32) -> Result<Option<Vec<article::Model>>>;
+    /// Full-text search over published articles, best matches first.
+    async fn search(&self, query: &SearchQuery, params: Params)
+        -> Result<Page<article::SearchHit>>;
 }
 
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait AuthorRepositoryTrait: Sync + Send {
     async fn create(&self, f: &AuthorCreate) -> Result<author::ActiveModel>;
+    /// Creates the author only while there are none, `None` otherwise.
+    async fn create_first(&self, f: &AuthorCreate) -> Result<Option<author::ActiveModel>>;
     async fn get_by_id(&self, id: i32) -> Result<Option<author::Model>>;
+    async fn find_by_email(&self, email: &str) -> Result<Option<author::Model>>;
+    async fn find_pages(&self, params: Params) -> Result<Page<author::Model>>;
+    async fn update(&self, id: i32, f: &AuthorUpdate) -> Result<Option<author::Model>>;
+    async fn delete(&self, id: i32) -> Result<bool>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait TagRepositoryTrait: Sync + Send {
+    /// All tags ordered by slug, with the number of articles carrying each.
+    async fn find_tags(&self) -> Result<Vec<tag::WithCount>>;
+    async fn find_tag(&self, slug: &str) -> Result<Option<tag::Model>>;
+    /// `None` when the article does not exist.
+    async fn find_article_tags(&self, article_id: i32) -> Result<Option<Vec<tag::Model>>>;
+    /// Creates the tag on first use. `None` when the article does not exist.
+    async fn attach(&self, article_id: i32, slug: &str, name: &str) -> Result<Option<tag::Model>>;
+    async fn detach(&self, article_id: i32, slug: &str) -> Result<bool>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait CommentRepositoryTrait: Sync + Send {
+    /// `None` when the article does not exist, otherwise its comments ordered by id.
+    async fn find_comments(&self, article_id: i32) -> Result<Option<Vec<comment::Model>>>;
+    async fn find_comment(&self, article_id: i32, id: i32) -> Result<Option<comment::Model>>;
+    async fn create(&self, f: &CommentCreate) -> Result<comment::Model>;
+    async fn update(&self, article_id: i32, id: i32, body: &str) -> Result<Option<comment::Model>>;
+    /// Replies are deleted along with the comment.
+    async fn delete(&self, article_id: i32, id: i32) -> Result<bool>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait TokenRepositoryTrait: Sync + Send {
+    async fn create(&self, f: &TokenCreate) -> Result<api_token::Model>;
+    /// The token with this hash, expired or not, and its author.
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<(api_token::Model, author::Model)>>;
+    /// The tokens of an author, newest first.
+    async fn find_tokens(&self, author_id: i32) -> Result<Vec<api_token::Model>>;
+    async fn delete(&self, author_id: i32, id: i32) -> Result<bool>;
+}
+
+/// Entries are written by [`ArticleRepositoryTrait::update_status`] and
+/// [`ArticleRepositoryTrait::publish_due`], in the same transaction as the article.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait OutboxRepositoryTrait: Sync + Send {
+    /// Claims the oldest pending entry due at `now` by moving its
+    /// `next_attempt_at` to `lease_until`, so that other dispatchers leave it
+    /// alone while it is delivered. `None` when nothing is due.
+    async fn claim_due(
+        &self,
+        now: DateTimeUtc,
+        lease_until: DateTimeUtc,
+    ) -> Result<Option<outbox::Model>>;
+    /// Newest first.
+    async fn find_entries(
+        &self,
+        state: Option<outbox::State>,
+        params: Params,
+    ) -> Result<Page<outbox::Model>>;
+    async fn find_entry(&self, id: i32) -> Result<Option<outbox::Model>>;
+    async fn update_entry(&self, id: i32, f: &OutboxUpdate) -> Result<Option<outbox::Model>>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait PublicationRepositoryTrait: Sync + Send {
+    /// `None` when the article does not exist, otherwise its publications ordered by channel.
+    async fn find_publications(&self, article_id: i32) -> Result<Option<Vec<publication::Model>>>;
+    /// Counts the attempt on the publication of the article on the channel,
+    /// creating it on the first one. A failure keeps the remote id and URL.
+    async fn record_attempt(&self, f: &PublicationAttempt) -> Result<publication::Model>;
+}
+
+/// Revisions are written by [`ArticleRepositoryTrait::create`] and
+/// [`ArticleRepositoryTrait::update`], in the same transaction as the article.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait RevisionRepositoryTrait: Sync + Send {
+    /// `None` when the article does not exist, otherwise its revisions, oldest first.
+    async fn find_revisions(&self, article_id: i32) -> Result<Option<Vec<revision::Model>>>;
+    async fn find_revision(&self, article_id: i32, rev: i32) -> Result<Option<revision::Model>>;
 }
 
 #[async_trait]
 pub trait Repository:
-    ArticleRepositoryTrait + AuthorRepositoryTrait + Sync + Send + std::fmt::Debug
+    ArticleRepositoryTrait
+    + AuthorRepositoryTrait
+    + TagRepositoryTrait
+    + CommentRepositoryTrait
+    + RevisionRepositoryTrait
+    + TokenRepositoryTrait
+    + OutboxRepositoryTrait
+    + PublicationRepositoryTrait
+    + Sync
+    + Send
+    + std::fmt::Debug
 {
 }
 
+/// Title matches weigh ten times as much as content matches. bm25 scores are
+/// negative, lower is better.
+const SEARCH_SQL: &str = r#"
+SELECT articles.*,
+       -bm25(articles_fts, 10.0, 1.0) AS score,
+       snippet(articles_fts, -1, char(2), char(3), '…', 16) AS snippet
+FROM articles_fts
+JOIN articles ON articles.id = articles_fts.rowid
+WHERE articles_fts MATCH ? AND articles.status = 'published'
+ORDER BY score DESC, articles.id
+LIMIT ? OFFSET ?
+"#;
+
+/// Checking for authors and inserting in one statement keeps two concurrent
+/// sign-ups from both becoming the first.
+const CREATE_FIRST_AUTHOR_SQL: &str = r#"
+INSERT INTO authors (first_name, last_name, email, password_hash, role)
+SELECT ?, ?, ?, ?, ?
+WHERE NOT EXISTS (SELECT 1 FROM authors)
+"#;
+
+const SEARCH_COUNT_SQL: &str = r#"
+SELECT count(*) AS total
+FROM articles_fts
+JOIN articles ON articles.id = articles_fts.rowid
+WHERE articles_fts MATCH ? AND articles.status = 'published'
+"#;
+
+/// Snapshots `article` as its next revision.
+async fn record_revision(
+    conn: &impl ConnectionTrait,
+    article: &article::Model,
+    editor_id: Option<i32>,
+) -> Result<(), DbErr> {
+    let last = revision::Entity::find()
+        .filter(revision::Column::ArticleId.eq(article.id))
+        .order_by_desc(revision::Column::Rev)
+        .one(conn)
+        .await?;
+    revision::ActiveModel {
+        article_id: Set(article.id),
+        rev: Set(last.map_or(1, |last| last.rev + 1)),
+        title: Set(article.title.clone()),
+        content: Set(article.content.clone()),
+        content_html: Set(article.content_html.clone()),
+        editor_id: Set(editor_id),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+    .map(|_| ())
+}
+
+/// Queues `event` for delivery right away.
+async fn enqueue(
+    conn: &impl ConnectionTrait,
+    event: outbox::Event,
+    article_id: i32,
+) -> Result<(), DbErr> {
+    let now = chrono::Utc::now();
+    outbox::ActiveModel {
+        event: Set(event),
+        article_id: Set(article_id),
+        state: Set(outbox::State::Pending),
+        attempts: Set(0),
+        next_attempt_at: Set(now),
+        last_error: Set(None),
+        created_at: Set(now),
+        delivered_at: Set(None),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+    .map(|_| ())
+}
+
+/// The first candidate for `base` that no other article uses as its current
+/// or an old slug.
+async fn unique_slug(
+    conn: &impl ConnectionTrait,
+    base: &str,
+    article_id: Option<i32>,
+) -> Result<String, DbErr> {
+    let mut n = 1;
+    loop {
+        let candidate = slug::candidate(base, n);
+        let mut current =
+            article::Entity::find().filter(article::Column::Slug.eq(candidate.as_str()));
+        let mut old =
+            article_slug::Entity::find().filter(article_slug::Column::Slug.eq(candidate.as_str()));
+        if let Some(id) = article_id {
+            current = current.filter(article::Column::Id.ne(id));
+            old = old.filter(article_slug::Column::ArticleId.ne(id));
+        }
+        if current.count(conn).await? == 0 && old.count(conn).await? == 0 {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+/// Keeps the current slug of `article` for redirects. `next` may be one of
+/// its own old slugs, which becomes current again.
+async fn retire_slug(
+    conn: &impl ConnectionTrait,
+    article: &article::Model,
+    next: &str,
+) -> Result<(), DbErr> {
+    article_slug::Entity::delete_many()
+        .filter(article_slug::Column::ArticleId.eq(article.id))
+        .filter(article_slug::Column::Slug.eq(next))
+        .exec(conn)
+        .await?;
+    article_slug::ActiveModel {
+        article_id: Set(article.id),
+        slug: Set(article.slug.clone()),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+    .map(|_| ())
+}
+
+fn articles(filter: &ArticleFilter) -> Select<article::Entity> {
+    let mut query = article::Entity::find();
+    if let Some(slug) = &filter.tag {
+        query = query
+            .inner_join(tag::Entity)
+            .filter(tag::Column::Slug.eq(slug.as_str()));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(article::Column::Status.eq(status));
+    }
+    if let Some(author_id) = filter.author_id {
+        query = query.filter(article::Column::AuthorId.eq(author_id));
+    }
+    query
+}
+
 #[derive(Debug, Clone)]
 pub struct DbRepository(Arc<DatabaseConnection>);
 
@@ -52,13 +416,22 @@ impl Repository for DbRepository {}
 #[async_trait]
 impl ArticleRepositoryTrait for DbRepository {
     async fn create(&self, f: &ArticleCreate) -> Result<article::ActiveModel> {
-        article::ActiveModel {
+        let txn = self.0.begin().await?;
+        let slug = unique_slug(&txn, &slug::for_title(&f.title), None).await?;
+        let created = article::ActiveModel {
             title: Set(f.title.to_owned()),
+            slug: Set(slug),
+            content: Set(f.content.clone()),
+            content_html: Set(f.content.as_deref().map(markdown::render)),
+            author_id: Set(f.author_id),
+            status: Set(article::Status::Draft),
             ..Default::default()
         }
-        .save(self.0.as_ref())
-        .await
-        .map_err(Into::into)
+        .save(&txn)
+        .await?;
+        record_revision(&txn, &article::Model::from(created.clone()), f.author_id).await?;
+        txn.commit().await?;
+        Ok(created)
     }
     async fn find_by_id(&self, id: i32) -> Result<Option<article::Model>> {
         article::Entity::find_by_id(id)
@@ -66,14 +439,206 @@ impl ArticleRepositoryTrait for DbRepository {
             .await
             .map_err(|e| e.into())
     }
-    async fn find_pages(&self, page: i32, page_size: i32) -> Result<Vec<article::Model>> {
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<article::Model>> {
+        let conn = self.0.as_ref();
+        let current = article::Entity::find()
+            .filter(article::Column::Slug.eq(slug))
+            .one(conn)
+            .await?;
+        if current.is_some() {
+            return Ok(current);
+        }
         article::Entity::find()
+            .inner_join(article_slug::Entity)
+            .filter(article_slug::Column::Slug.eq(slug))
+            .one(conn)
+            .await
+            .map_err(Into::into)
+    }
+    async fn find_pages(
+        &self,
+        filter: &ArticleFilter,
+        params: Params,
+    ) -> Result<Page<article::Model>> {
+        let paginator = articles(filter)
+            .order_by_asc(article::Column::Id)
+            .paginate(self.0.as_ref(), params.page_size());
+        let total_items = paginator.num_items().await?;
+        let items = paginator.fetch_page(params.index()).await?;
+        Ok(Page::new(items, params, total_items))
+    }
+    async fn find_after(
+        &self,
+        filter: &ArticleFilter,
+        params: KeysetParams,
+    ) -> Result<Slice<article::Model>> {
+        let mut query = articles(filter).order_by_asc(article::Column::Id);
+        if let Some(after) = params.after() {
+            query = query.filter(article::Column::Id.gt(after.0));
+        }
+        let items = query.limit(params.limit() + 1).all(self.0.as_ref()).await?;
+        Ok(Slice::new(items, params, |article| article.id))
+    }
+    async fn update(&self, id: i32, f: &ArticleUpdate) -> Result<Option<article::Model>> {
+        let mut am = article::ActiveModel {
+            id: Unchanged(id),
+            title: f.title.clone().map_or(NotSet, Set),
+            slug: NotSet,
+            content: f.content.clone().map_or(NotSet, Set),
+            content_html: f.content.as_ref().map_or(NotSet, |content| {
+                Set(content.as_deref().map(markdown::render))
+            }),
+            author_id: NotSet,
+            status: NotSet,
+            published_at: NotSet,
+            publish_at: NotSet,
+        };
+        let txn = self.0.begin().await?;
+        let Some(current) = article::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(None);
+        };
+        // A title that still yields the current slug keeps it, suffix included.
+        if let Some(title) = &f.title {
+            let base = slug::for_title(title);
+            if !slug::is_candidate(&current.slug, &base) {
+                let next = unique_slug(&txn, &base, Some(id)).await?;
+                retire_slug(&txn, &current, &next).await?;
+                am.slug = Set(next);
+            }
+        }
+        let updated = match am.update(&txn).await {
+            Ok(m) => m,
+            Err(DbErr::RecordNotUpdated) | Err(DbErr::RecordNotFound(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        record_revision(&txn, &updated, f.editor_id).await?;
+        txn.commit().await?;
+        Ok(Some(updated))
+    }
+    async fn update_status(&self, id: i32, f: &StatusUpdate) -> Result<Option<article::Model>> {
+        let txn = self.0.begin().await?;
+        let Some(before) = article::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(None);
+        };
+        let updated = article::ActiveModel {
+            id: Unchanged(id),
+            status: Set(f.status),
+            published_at: Set(f.published_at),
+            publish_at: Set(f.publish_at),
+            ..Default::default()
+        }
+        .update(&txn)
+        .await?;
+        if before.status != article::Status::Published
+            && updated.status == article::Status::Published
+        {
+            enqueue(&txn, outbox::Event::ArticlePublished, id).await?;
+        }
+        txn.commit().await?;
+        Ok(Some(updated))
+    }
+    async fn publish_due(&self, now: DateTimeUtc) -> Result<Vec<article::Model>> {
+        let txn = self.0.begin().await?;
+        let due: Vec<i32> = article::Entity::find()
+            .select_only()
+            .column(article::Column::Id)
+            .filter(article::Column::PublishAt.lte(now))
+            .filter(
+                article::Column::Status.is_in([article::Status::Draft, article::Status::InReview]),
+            )
+            .into_tuple()
+            .all(&txn)
+            .await?;
+        if due.is_empty() {
+            return Ok(vec![]);
+        }
+        article::Entity::update_many()
+            .col_expr(
+                article::Column::Status,
+                Expr::value(article::Status::Published),
+            )
+            .col_expr(
+                article::Column::PublishedAt,
+                Expr::col(article::Column::PublishAt).into(),
+            )
+            .col_expr(
+                article::Column::PublishAt,
+                Expr::value(Option::<DateTimeUtc>::None),
+            )
+            .filter(article::Column::Id.is_in(due.clone()))
+            .exec(&txn)
+            .await?;
+        for id in &due {
+            enqueue(&txn, outbox::Event::ArticlePublished, *id).await?;
+        }
+        let published = article::Entity::find()
+            .filter(article::Column::Id.is_in(due))
             .order_by_asc(article::Column::Id)
-            .paginate(self.0.as_ref(), page_size as u64)
-            .fetch_page(page as u64)
+            .all(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(published)
+    }
+    async fn delete(&self, id: i32) -> Result<bool> {
+        article::Entity::delete_by_id(id)
+            .exec(self.0.as_ref())
             .await
+            .map(|r| r.rows_affected > 0)
             .map_err(Into::into)
     }
+    async fn find_with_author(&self, id: i32) -> Result<Option<article::WithAuthor>> {
+        let found = article::Entity::find_by_id(id)
+            .find_also_related(author::Entity)
+            .one(self.0.as_ref())
+            .await?;
+        Ok(found.map(|(article, author)| article::WithAuthor { article, author }))
+    }
+    async fn find_by_author(&self, author_id: i32) -> Result<Option<Vec<article::Model>>> {
+        let found = author::Entity::find_by_id(author_id)
+            .find_with_related(article::Entity)
+            .order_by_asc(article::Column::Id)
+            .all(self.0.as_ref())
+            .await?;
+        Ok(found.into_iter().next().map(|(_author, articles)| articles))
+    }
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        params: Params,
+    ) -> Result<Page<article::SearchHit>> {
+        let conn = self.0.as_ref();
+        let count = Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            SEARCH_COUNT_SQL,
+            [query.as_fts().into()],
+        );
+        let total_items = match conn.query_one(count).await? {
+            Some(row) => row.try_get::<i64>("", "total")? as u64,
+            None => 0,
+        };
+        let select = Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            SEARCH_SQL,
+            [
+                query.as_fts().into(),
+                (params.page_size() as i64).into(),
+                ((params.index() * params.page_size()) as i64).into(),
+            ],
+        );
+        let hits = conn
+            .query_all(select)
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(article::SearchHit {
+                    article: article::Model::from_query_result(row, "")?,
+                    score: row.try_get("", "score")?,
+                    snippet: search::highlight(&row.try_get::<String>("", "snippet")?),
+                })
+            })
+            .collect::<Result<_, DbErr>>()?;
+        Ok(Page::new(hits, params, total_items))
+    }
 }
 
 #[async_trait]
@@ -83,34 +648,462 @@ impl AuthorRepositoryTrait for DbRepository {
             first_name: Set(f.first_name.to_owned()),
             last_name: Set(f.last_name.to_owned()),
             email: Set(f.email.to_owned()),
+            password_hash: Set(f.password.as_deref().map(auth::hash_password).transpose()?),
+            role: Set(f.role),
             ..Default::default()
         }
         .save(self.0.as_ref())
         .await
         .map_err(Into::into)
     }
+    async fn create_first(&self, f: &AuthorCreate) -> Result<Option<author::ActiveModel>> {
+        let password_hash = f.password.as_deref().map(auth::hash_password).transpose()?;
+        let insert = Statement::from_sql_and_values(
+            self.0.get_database_backend(),
+            CREATE_FIRST_AUTHOR_SQL,
+            [
+                f.first_name.as_str().into(),
+                f.last_name.as_str().into(),
+                f.email.as_str().into(),
+                password_hash.into(),
+                f.role.to_value().into(),
+            ],
+        );
+        let inserted = self.0.execute(insert).await?;
+        if inserted.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let id = inserted.last_insert_id() as i32;
+        let created = author::Entity::find_by_id(id).one(self.0.as_ref()).await?;
+        Ok(created.map(Into::into))
+    }
     async fn get_by_id(&self, id: i32) -> Result<Option<author::Model>> {
         author::Entity::find_by_id(id)
             .one(self.0.as_ref())
             .await
             .map_err(Into::into)
     }
+    async fn find_by_email(&self, email: &str) -> Result<Option<author::Model>> {
+        author::Entity::find()
+            .filter(author::Column::Email.eq(email))
+            .one(self.0.as_ref())
+            .await
+            .map_err(Into::into)
+    }
+    async fn find_pages(&self, params: Params) -> Result<Page<author::Model>> {
+        let paginator = author::Entity::find()
+            .order_by_asc(author::Column::Id)
+            .paginate(self.0.as_ref(), params.page_size());
+        let total_items = paginator.num_items().await?;
+        let items = paginator.fetch_page(params.index()).await?;
+        Ok(Page::new(items, params, total_items))
+    }
+    async fn update(&self, id: i32, f: &AuthorUpdate) -> Result<Option<author::Model>> {
+        let am = author::ActiveModel {
+            id: Unchanged(id),
+            first_name: f.first_name.clone().map_or(NotSet, Set),
+            last_name: f.last_name.clone().map_or(NotSet, Set),
+            email: f.email.clone().map_or(NotSet, Set),
+            password_hash: match &f.password {
+                Some(password) => Set(Some(auth::hash_password(password)?)),
+                None => NotSet,
+            },
+            role: f.role.map_or(NotSet, Set),
+        };
+        match am.update(self.0.as_ref()).await {
+            Ok(m) => Ok(Some(m)),
+            Err(DbErr::RecordNotUpdated) | Err(DbErr::RecordNotFound(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    async fn delete(&self, id: i32) -> Result<bool> {
+        author::Entity::delete_by_id(id)
+            .exec(self.0.as_ref())
+            .await
+            .map(|r| r.rows_affected > 0)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl TagRepositoryTrait for DbRepository {
+    async fn find_tags(&self) -> Result<Vec<tag::WithCount>> {
+        tag::Entity::find()
+            .column_as(article_tag::Column::ArticleId.count(), "article_count")
+            .left_join(article_tag::Entity)
+            .group_by(tag::Column::Id)
+            .order_by_asc(tag::Column::Slug)
+            .into_model()
+            .all(self.0.as_ref())
+            .await
+            .map_err(Into::into)
+    }
+    async fn find_tag(&self, slug: &str) -> Result<Option<tag::Model>> {
+        tag::Entity::find()
+            .filter(tag::Column::Slug.eq(slug))
+            .one(self.0.as_ref())
+            .await
+            .map_err(Into::into)
+    }
+    async fn find_article_tags(&self, article_id: i32) -> Result<Option<Vec<tag::Model>>> {
+        let Some(article) = article::Entity::find_by_id(article_id)
+            .one(self.0.as_ref())
+            .await?
+        else {
+            return Ok(None);
+        };
+        let tags = article
+            .find_related(tag::Entity)
+            .order_by_asc(tag::Column::Slug)
+            .all(self.0.as_ref())
+            .await?;
+        Ok(Some(tags))
+    }
+    async fn attach(&self, article_id: i32, slug: &str, name: &str) -> Result<Option<tag::Model>> {
+        let conn = self.0.as_ref();
+        if article::Entity::find_by_id(article_id)
+            .one(conn)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        tag::Entity::insert(tag::ActiveModel {
+            slug: Set(slug.to_owned()),
+            name: Set(name.to_owned()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::column(tag::Column::Slug)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(conn)
+        .await?;
+        let tag = self
+            .find_tag(slug)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("tag `{slug}`")))?;
+        article_tag::Entity::insert(article_tag::ActiveModel {
+            article_id: Set(article_id),
+            tag_id: Set(tag.id),
+        })
+        .on_conflict(
+            OnConflict::columns([article_tag::Column::ArticleId, article_tag::Column::TagId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(conn)
+        .await?;
+        Ok(Some(tag))
+    }
+    async fn detach(&self, article_id: i32, slug: &str) -> Result<bool> {
+        let Some(tag) = self.find_tag(slug).await? else {
+            return Ok(false);
+        };
+        article_tag::Entity::delete_by_id((article_id, tag.id))
+            .exec(self.0.as_ref())
+            .await
+            .map(|r| r.rows_affected > 0)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl CommentRepositoryTrait for DbRepository {
+    async fn find_comments(&self, article_id: i32) -> Result<Option<Vec<comment::Model>>> {
+        let found = article::Entity::find_by_id(article_id)
+            .find_with_related(comment::Entity)
+            .order_by_asc(comment::Column::Id)
+            .all(self.0.as_ref())
+            .await?;
+        Ok(found
+            .into_iter()
+            .next()
+            .map(|(_article, comments)| comments))
+    }
+    async fn find_comment(&self, article_id: i32, id: i32) -> Result<Option<comment::Model>> {
+        comment::Entity::find_by_id(id)
+            .filter(comment::Column::ArticleId.eq(article_id))
+            .one(self.0.as_ref())
+            .await
+            .map_err(Into::into)
+    }
+    async fn create(&self, f: &CommentCreate) -> Result<comment::Model> {
+        comment::ActiveModel {
+            article_id: Set(f.article_id),
+            parent_id: Set(f.parent_id),
+            author_name: Set(f.author_name.to_owned()),
+            body: Set(f.body.to_owned()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(self.0.as_ref())
+        .await
+        .map_err(Into::into)
+    }
+    async fn update(&self, article_id: i32, id: i32, body: &str) -> Result<Option<comment::Model>> {
+        if self.find_comment(article_id, id).await?.is_none() {
+            return Ok(None);
+        }
+        let am = comment::ActiveModel {
+            id: Unchanged(id),
+            body: Set(body.to_owned()),
+            ..Default::default()
+        };
+        match am.update(self.0.as_ref()).await {
+            Ok(m) => Ok(Some(m)),
+            Err(DbErr::RecordNotUpdated) | Err(DbErr::RecordNotFound(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    async fn delete(&self, article_id: i32, id: i32) -> Result<bool> {
+        comment::Entity::delete_many()
+            .filter(comment::Column::Id.eq(id))
+            .filter(comment::Column::ArticleId.eq(article_id))
+            .exec(self.0.as_ref())
+            .await
+            .map(|r| r.rows_affected > 0)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl RevisionRepositoryTrait for DbRepository {
+    async fn find_revisions(&self, article_id: i32) -> Result<Option<Vec<revision::Model>>> {
+        let found = article::Entity::find_by_id(article_id)
+            .find_with_related(revision::Entity)
+            .order_by_asc(revision::Column::Rev)
+            .all(self.0.as_ref())
+            .await?;
+        Ok(found
+            .into_iter()
+            .next()
+            .map(|(_article, revisions)| revisions))
+    }
+    async fn find_revision(&self, article_id: i32, rev: i32) -> Result<Option<revision::Model>> {
+        revision::Entity::find()
+            .filter(revision::Column::ArticleId.eq(article_id))
+            .filter(revision::Column::Rev.eq(rev))
+            .one(self.0.as_ref())
+            .await
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl TokenRepositoryTrait for DbRepository {
+    async fn create(&self, f: &TokenCreate) -> Result<api_token::Model> {
+        api_token::ActiveModel {
+            author_id: Set(f.author_id),
+            name: Set(f.name.to_owned()),
+            token_hash: Set(f.token_hash.to_owned()),
+            scopes: Set(api_token::Scope::join(&f.scopes)),
+            created_at: Set(chrono::Utc::now()),
+            expires_at: Set(f.expires_at),
+            ..Default::default()
+        }
+        .insert(self.0.as_ref())
+        .await
+        .map_err(Into::into)
+    }
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<(api_token::Model, author::Model)>> {
+        let found = api_token::Entity::find()
+            .filter(api_token::Column::TokenHash.eq(token_hash))
+            .find_also_related(author::Entity)
+            .one(self.0.as_ref())
+            .await?;
+        Ok(found.and_then(|(token, author)| Some((token, author?))))
+    }
+    async fn find_tokens(&self, author_id: i32) -> Result<Vec<api_token::Model>> {
+        api_token::Entity::find()
+            .filter(api_token::Column::AuthorId.eq(author_id))
+            .order_by_desc(api_token::Column::Id)
+            .all(self.0.as_ref())
+            .await
+            .map_err(Into::into)
+    }
+    async fn delete(&self, author_id: i32, id: i32) -> Result<bool> {
+        api_token::Entity::delete_many()
+            .filter(api_token::Column::Id.eq(id))
+            .filter(api_token::Column::AuthorId.eq(author_id))
+            .exec(self.0.as_ref())
+            .await
+            .map(|r| r.rows_affected > 0)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl OutboxRepositoryTrait for DbRepository {
+    async fn claim_due(
+        &self,
+        now: DateTimeUtc,
+        lease_until: DateTimeUtc,
+    ) -> Result<Option<outbox::Model>> {
+        let txn = self.0.begin().await?;
+        loop {
+            let due = outbox::Entity::find()
+                .filter(outbox::Column::State.eq(outbox::State::Pending))
+                .filter(outbox::Column::NextAttemptAt.lte(now))
+                .order_by_asc(outbox::Column::Id)
+                .one(&txn)
+                .await?;
+            let Some(entry) = due else {
+                txn.commit().await?;
+                return Ok(None);
+            };
+            // Only succeeds if no one else claimed or changed the entry since.
+            let claimed = outbox::Entity::update_many()
+                .col_expr(outbox::Column::NextAttemptAt, Expr::value(lease_until))
+                .filter(outbox::Column::Id.eq(entry.id))
+                .filter(outbox::Column::State.eq(outbox::State::Pending))
+                .filter(outbox::Column::NextAttemptAt.eq(entry.next_attempt_at))
+                .exec(&txn)
+                .await?;
+            if claimed.rows_affected == 1 {
+                txn.commit().await?;
+                return Ok(Some(outbox::Model {
+                    next_attempt_at: lease_until,
+                    ..entry
+                }));
+            }
+        }
+    }
+    async fn find_entries(
+        &self,
+        state: Option<outbox::State>,
+        params: Params,
+    ) -> Result<Page<outbox::Model>> {
+        let mut query = outbox::Entity::find().order_by_desc(outbox::Column::Id);
+        if let Some(state) = state {
+            query = query.filter(outbox::Column::State.eq(state));
+        }
+        let paginator = query.paginate(self.0.as_ref(), params.page_size());
+        let total_items = paginator.num_items().await?;
+        let items = paginator.fetch_page(params.index()).await?;
+        Ok(Page::new(items, params, total_items))
+    }
+    async fn find_entry(&self, id: i32) -> Result<Option<outbox::Model>> {
+        outbox::Entity::find_by_id(id)
+            .one(self.0.as_ref())
+            .await
+            .map_err(Into::into)
+    }
+    async fn update_entry(&self, id: i32, f: &OutboxUpdate) -> Result<Option<outbox::Model>> {
+        let am = outbox::ActiveModel {
+            id: Unchanged(id),
+            state: Set(f.state),
+            attempts: Set(f.attempts),
+            next_attempt_at: Set(f.next_attempt_at),
+            last_error: Set(f.last_error.clone()),
+            delivered_at: Set(f.delivered_at),
+            ..Default::default()
+        };
+        match am.update(self.0.as_ref()).await {
+            Ok(m) => Ok(Some(m)),
+            Err(DbErr::RecordNotUpdated) | Err(DbErr::RecordNotFound(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl PublicationRepositoryTrait for DbRepository {
+    async fn find_publications(&self, article_id: i32) -> Result<Option<Vec<publication::Model>>> {
+        if article::Entity::find_by_id(article_id)
+            .one(self.0.as_ref())
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let publications = publication::Entity::find()
+            .filter(publication::Column::ArticleId.eq(article_id))
+            .order_by_asc(publication::Column::Channel)
+            .all(self.0.as_ref())
+            .await?;
+        Ok(Some(publications))
+    }
+    async fn record_attempt(&self, f: &PublicationAttempt) -> Result<publication::Model> {
+        let txn = self.0.begin().await?;
+        let now = chrono::Utc::now();
+        let existing = publication::Entity::find()
+            .filter(publication::Column::ArticleId.eq(f.article_id))
+            .filter(publication::Column::Channel.eq(f.channel.as_str()))
+            .one(&txn)
+            .await?;
+        let mut am = match existing {
+            Some(p) => {
+                let attempts = p.attempts + 1;
+                let mut am: publication::ActiveModel = p.into();
+                am.attempts = Set(attempts);
+                am
+            }
+            None => publication::ActiveModel {
+                article_id: Set(f.article_id),
+                channel: Set(f.channel.clone()),
+                remote_id: Set(None),
+                remote_url: Set(None),
+                attempts: Set(1),
+                last_error: Set(None),
+                posted_at: Set(None),
+                ..Default::default()
+            },
+        };
+        am.updated_at = Set(now);
+        match &f.error {
+            Some(error) => {
+                am.state = Set(publication::State::Failed);
+                am.last_error = Set(Some(error.clone()));
+            }
+            None => {
+                am.state = Set(publication::State::Posted);
+                am.remote_id = Set(f.remote_id.clone());
+                am.remote_url = Set(f.remote_url.clone());
+                am.posted_at = Set(Some(now));
+            }
+        }
+        let saved = match am.id.is_not_set() {
+            true => am.insert(&txn).await?,
+            false => am.update(&txn).await?,
+        };
+        txn.commit().await?;
+        Ok(saved)
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::{
-        ArticleCreate, ArticleRepositoryTrait, AuthorCreate, AuthorRepositoryTrait,
-        MockArticleRepositoryTrait, MockAuthorRepositoryTrait, Repository,
+        ArticleCreate, ArticleFilter, ArticleRepositoryTrait, ArticleUpdate, AuthorCreate,
+        AuthorRepositoryTrait, AuthorUpdate, CommentCreate, CommentRepositoryTrait,
+        MockArticleRepositoryTrait, MockAuthorRepositoryTrait, MockCommentRepositoryTrait,
+        MockOutboxRepositoryTrait, MockPublicationRepositoryTrait, MockRevisionRepositoryTrait,
+        MockTagRepositoryTrait, MockTokenRepositoryTrait, OutboxRepositoryTrait, OutboxUpdate,
+        PublicationAttempt, PublicationRepositoryTrait, Repository, RevisionRepositoryTrait,
+        StatusUpdate, TagRepositoryTrait, TokenCreate, TokenRepositoryTrait,
     };
-    use crate::domain::{article, author};
-    use anyhow::Result;
+    use crate::domain::{api_token, article, author, comment, outbox, publication, revision, tag};
+    use crate::error::Result;
+    use crate::pagination::{KeysetParams, Page, Params, Slice};
+    use crate::search::SearchQuery;
+    use sea_orm::prelude::DateTimeUtc;
     use std::fmt::Debug;
 
     #[derive(Debug)]
     pub struct MockRepository {
         article_repo: MockArticleRepositoryTrait,
         author_repo: MockAuthorRepositoryTrait,
+        tag_repo: MockTagRepositoryTrait,
+        comment_repo: MockCommentRepositoryTrait,
+        revision_repo: MockRevisionRepositoryTrait,
+        token_repo: MockTokenRepositoryTrait,
+        outbox_repo: MockOutboxRepositoryTrait,
+        publication_repo: MockPublicationRepositoryTrait,
     }
     impl MockRepository {
         pub fn new(
@@ -120,6 +1113,48 @@ pub mod tests {
             Self {
                 article_repo,
                 author_repo,
+                tag_repo: MockTagRepositoryTrait::new(),
+                comment_repo: MockCommentRepositoryTrait::new(),
+                revision_repo: MockRevisionRepositoryTrait::new(),
+                token_repo: MockTokenRepositoryTrait::new(),
+                outbox_repo: MockOutboxRepositoryTrait::new(),
+                publication_repo: MockPublicationRepositoryTrait::new(),
+            }
+        }
+
+        pub fn with_tags(self, tag_repo: MockTagRepositoryTrait) -> Self {
+            Self { tag_repo, ..self }
+        }
+
+        pub fn with_comments(self, comment_repo: MockCommentRepositoryTrait) -> Self {
+            Self {
+                comment_repo,
+                ..self
+            }
+        }
+
+        pub fn with_revisions(self, revision_repo: MockRevisionRepositoryTrait) -> Self {
+            Self {
+                revision_repo,
+                ..self
+            }
+        }
+
+        pub fn with_tokens(self, token_repo: MockTokenRepositoryTrait) -> Self {
+            Self { token_repo, ..self }
+        }
+
+        pub fn with_outbox(self, outbox_repo: MockOutboxRepositoryTrait) -> Self {
+            Self {
+                outbox_repo,
+                ..self
+            }
+        }
+
+        pub fn with_publications(self, publication_repo: MockPublicationRepositoryTrait) -> Self {
+            Self {
+                publication_repo,
+                ..self
             }
         }
     }
@@ -143,6 +1178,23 @@ pub mod tests {
             self.author_repo.create(f)
         }
 
+        fn create_first<'a, 'b, 'c>(
+            &'a self,
+            f: &'b AuthorCreate,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<author::ActiveModel>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.author_repo.create_first(f)
+        }
+
         fn get_by_id<'a, 'b>(
             &'a self,
             id: i32,
@@ -158,32 +1210,30 @@ pub mod tests {
         {
             self.author_repo.get_by_id(id)
         }
-    }
 
-    impl ArticleRepositoryTrait for MockRepository {
-        fn find_by_id<'a, 'b>(
+        fn find_by_email<'a, 'b, 'c>(
             &'a self,
-            id: i32,
+            email: &'b str,
         ) -> ::core::pin::Pin<
             Box<
-                dyn ::core::future::Future<Output = Result<Option<article::Model>>>
+                dyn ::core::future::Future<Output = Result<Option<author::Model>>>
                     + ::core::marker::Send
-                    + 'b,
+                    + 'c,
             >,
         >
         where
-            'a: 'b,
+            'a: 'c,
+            'b: 'c,
         {
-            self.article_repo.find_by_id(id)
+            self.author_repo.find_by_email(email)
         }
 
         fn find_pages<'a, 'b>(
             &'a self,
-            page: i32,
-            page_size: i32,
+            params: Params,
         ) -> ::core::pin::Pin<
             Box<
-                dyn ::core::future::Future<Output = Result<Vec<article::Model>>>
+                dyn ::core::future::Future<Output = Result<Page<author::Model>>>
                     + ::core::marker::Send
                     + 'b,
             >,
@@ -191,15 +1241,16 @@ pub mod tests {
         where
             'a: 'b,
         {
-            self.article_repo.find_pages(page, page_size)
+            self.author_repo.find_pages(params)
         }
 
-        fn create<'a, 'b, 'c>(
+        fn update<'a, 'b, 'c>(
             &'a self,
-            f: &'b ArticleCreate,
+            id: i32,
+            f: &'b AuthorUpdate,
         ) -> ::core::pin::Pin<
             Box<
-                dyn ::core::future::Future<Output = Result<article::ActiveModel>>
+                dyn ::core::future::Future<Output = Result<Option<author::Model>>>
                     + ::core::marker::Send
                     + 'c,
             >,
@@ -208,7 +1259,595 @@ pub mod tests {
             'a: 'c,
             'b: 'c,
         {
-            self.article_repo.create(f)
+            self.author_repo.update(id, f)
+        }
+
+        fn delete<'a, 'b>(
+            &'a self,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<dyn ::core::future::Future<Output = Result<bool>> + ::core::marker::Send + 'b>,
+        >
+        where
+            'a: 'b,
+        {
+            self.author_repo.delete(id)
+        }
+    }
+
+    impl ArticleRepositoryTrait for MockRepository {
+        fn find_by_id<'a, 'b>(
+            &'a self,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<article::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.article_repo.find_by_id(id)
+        }
+
+        fn find_by_slug<'a, 'b, 'c>(
+            &'a self,
+            slug: &'b str,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<article::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.article_repo.find_by_slug(slug)
+        }
+
+        fn find_pages<'a, 'b, 'c>(
+            &'a self,
+            filter: &'b ArticleFilter,
+            params: Params,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Page<article::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.article_repo.find_pages(filter, params)
+        }
+
+        fn find_after<'a, 'b, 'c>(
+            &'a self,
+            filter: &'b ArticleFilter,
+            params: KeysetParams,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Slice<article::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.article_repo.find_after(filter, params)
+        }
+
+        fn create<'a, 'b, 'c>(
+            &'a self,
+            f: &'b ArticleCreate,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<article::ActiveModel>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.article_repo.create(f)
+        }
+
+        fn update<'a, 'b, 'c>(
+            &'a self,
+            id: i32,
+            f: &'b ArticleUpdate,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<article::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.article_repo.update(id, f)
+        }
+
+        fn update_status<'a, 'b, 'c>(
+            &'a self,
+            id: i32,
+            f: &'b StatusUpdate,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<article::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.article_repo.update_status(id, f)
+        }
+
+        fn publish_due<'a, 'b>(
+            &'a self,
+            now: DateTimeUtc,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Vec<article::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.article_repo.publish_due(now)
+        }
+
+        fn delete<'a, 'b>(
+            &'a self,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<dyn ::core::future::Future<Output = Result<bool>> + ::core::marker::Send + 'b>,
+        >
+        where
+            'a: 'b,
+        {
+            self.article_repo.delete(id)
+        }
+
+        fn find_with_author<'a, 'b>(
+            &'a self,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<article::WithAuthor>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.article_repo.find_with_author(id)
+        }
+
+        fn find_by_author<'a, 'b>(
+            &'a self,
+            author_id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<Vec<article::Model>>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.article_repo.find_by_author(author_id)
+        }
+
+        fn search<'a, 'b, 'c>(
+            &'a self,
+            query: &'b SearchQuery,
+            params: Params,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Page<article::SearchHit>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.article_repo.search(query, params)
+        }
+    }
+
+    impl TagRepositoryTrait for MockRepository {
+        fn find_tags<'a, 'b>(
+            &'a self,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Vec<tag::WithCount>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.tag_repo.find_tags()
+        }
+
+        fn find_tag<'a, 'b, 'c>(
+            &'a self,
+            slug: &'b str,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<tag::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.tag_repo.find_tag(slug)
+        }
+
+        fn find_article_tags<'a, 'b>(
+            &'a self,
+            article_id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<Vec<tag::Model>>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.tag_repo.find_article_tags(article_id)
+        }
+
+        fn attach<'a, 'b, 'c, 'd>(
+            &'a self,
+            article_id: i32,
+            slug: &'b str,
+            name: &'c str,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<tag::Model>>>
+                    + ::core::marker::Send
+                    + 'd,
+            >,
+        >
+        where
+            'a: 'd,
+            'b: 'd,
+            'c: 'd,
+        {
+            self.tag_repo.attach(article_id, slug, name)
+        }
+
+        fn detach<'a, 'b, 'c>(
+            &'a self,
+            article_id: i32,
+            slug: &'b str,
+        ) -> ::core::pin::Pin<
+            Box<dyn ::core::future::Future<Output = Result<bool>> + ::core::marker::Send + 'c>,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.tag_repo.detach(article_id, slug)
+        }
+    }
+
+    impl CommentRepositoryTrait for MockRepository {
+        fn find_comments<'a, 'b>(
+            &'a self,
+            article_id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<Vec<comment::Model>>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.comment_repo.find_comments(article_id)
+        }
+
+        fn find_comment<'a, 'b>(
+            &'a self,
+            article_id: i32,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<comment::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.comment_repo.find_comment(article_id, id)
+        }
+
+        fn create<'a, 'b, 'c>(
+            &'a self,
+            f: &'b CommentCreate,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<comment::Model>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.comment_repo.create(f)
+        }
+
+        fn update<'a, 'b, 'c>(
+            &'a self,
+            article_id: i32,
+            id: i32,
+            body: &'b str,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<comment::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.comment_repo.update(article_id, id, body)
+        }
+
+        fn delete<'a, 'b>(
+            &'a self,
+            article_id: i32,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<dyn ::core::future::Future<Output = Result<bool>> + ::core::marker::Send + 'b>,
+        >
+        where
+            'a: 'b,
+        {
+            self.comment_repo.delete(article_id, id)
+        }
+    }
+
+    impl RevisionRepositoryTrait for MockRepository {
+        fn find_revisions<'a, 'b>(
+            &'a self,
+            article_id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<Vec<revision::Model>>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.revision_repo.find_revisions(article_id)
+        }
+
+        fn find_revision<'a, 'b>(
+            &'a self,
+            article_id: i32,
+            rev: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<revision::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.revision_repo.find_revision(article_id, rev)
+        }
+    }
+
+    impl TokenRepositoryTrait for MockRepository {
+        fn create<'a, 'b, 'c>(
+            &'a self,
+            f: &'b TokenCreate,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<api_token::Model>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.token_repo.create(f)
+        }
+
+        fn find_by_hash<'a, 'b, 'c>(
+            &'a self,
+            token_hash: &'b str,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<
+                        Output = Result<Option<(api_token::Model, author::Model)>>,
+                    > + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.token_repo.find_by_hash(token_hash)
+        }
+
+        fn find_tokens<'a, 'b>(
+            &'a self,
+            author_id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Vec<api_token::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.token_repo.find_tokens(author_id)
+        }
+
+        fn delete<'a, 'b>(
+            &'a self,
+            author_id: i32,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<dyn ::core::future::Future<Output = Result<bool>> + ::core::marker::Send + 'b>,
+        >
+        where
+            'a: 'b,
+        {
+            self.token_repo.delete(author_id, id)
+        }
+    }
+
+    impl OutboxRepositoryTrait for MockRepository {
+        fn claim_due<'a, 'b>(
+            &'a self,
+            now: DateTimeUtc,
+            lease_until: DateTimeUtc,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<outbox::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.outbox_repo.claim_due(now, lease_until)
+        }
+
+        fn find_entries<'a, 'b>(
+            &'a self,
+            state: Option<outbox::State>,
+            params: Params,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Page<outbox::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.outbox_repo.find_entries(state, params)
+        }
+
+        fn find_entry<'a, 'b>(
+            &'a self,
+            id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<outbox::Model>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.outbox_repo.find_entry(id)
+        }
+
+        fn update_entry<'a, 'b, 'c>(
+            &'a self,
+            id: i32,
+            f: &'b OutboxUpdate,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<outbox::Model>>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.outbox_repo.update_entry(id, f)
+        }
+    }
+
+    impl PublicationRepositoryTrait for MockRepository {
+        fn find_publications<'a, 'b>(
+            &'a self,
+            article_id: i32,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<Option<Vec<publication::Model>>>>
+                    + ::core::marker::Send
+                    + 'b,
+            >,
+        >
+        where
+            'a: 'b,
+        {
+            self.publication_repo.find_publications(article_id)
+        }
+
+        fn record_attempt<'a, 'b, 'c>(
+            &'a self,
+            f: &'b PublicationAttempt,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<publication::Model>>
+                    + ::core::marker::Send
+                    + 'c,
+            >,
+        >
+        where
+            'a: 'c,
+            'b: 'c,
+        {
+            self.publication_repo.record_attempt(f)
         }
     }
 }
@@ -0,0 +1,72 @@
+//! Publishes scheduled articles and drains the outbox in the background.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::services::{ArticleServiceTrait, Dispatched, OutboxServiceTrait};
+
+/// Checks for due articles every `period`, starting right away. Failures are
+/// logged and retried on the next tick.
+pub fn spawn(service: Arc<dyn ArticleServiceTrait>, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match service.publish_scheduled().await {
+                Ok(0) => {}
+                Ok(published) => tracing::info!("published {published} scheduled article(s)"),
+                Err(e) => tracing::error!("publishing scheduled articles failed: {e}"),
+            }
+        }
+    })
+}
+
+/// Dispatches the due outbox entries every `period`, like [`spawn`].
+pub fn spawn_dispatcher(service: Arc<dyn OutboxServiceTrait>, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match service.dispatch_due().await {
+                Ok(dispatched) if dispatched == Dispatched::default() => {}
+                Ok(Dispatched {
+                    delivered,
+                    retrying,
+                    dead,
+                }) => tracing::info!(
+                    "outbox: {delivered} delivered, {retrying} to retry, {dead} dead"
+                ),
+                Err(e) => tracing::error!("dispatching the outbox failed: {e}"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::error::Error;
+    use crate::services::MockArticleServiceTrait;
+
+    #[tokio::test]
+    async fn keeps_running_after_errors() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let mut mock = MockArticleServiceTrait::new();
+        mock.expect_publish_scheduled().returning(move || {
+            match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(Error::Internal(anyhow::anyhow!("database is locked"))),
+                _ => Ok(1),
+            }
+        });
+
+        let task = super::spawn(Arc::new(mock), Duration::from_millis(5));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        task.abort();
+        assert!(calls.load(Ordering::SeqCst) >= 2);
+    }
+}
@@ -0,0 +1,265 @@
+//! Compares the live database schema against the SeaORM entities.
+//!
+//! The expected tables are created from the entities in a scratch in-memory
+//! SQLite database, so both sides are read back through `PRAGMA table_info`
+//! and compared on the same terms.
+
+use sea_orm::sea_query::TableCreateStatement;
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, EntityName, Schema, Statement,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::domain::{
+    api_token, article, article_slug, article_tag, author, comment, outbox, publication, revision,
+    tag,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    MissingTable {
+        table: String,
+    },
+    MissingColumn {
+        table: String,
+        column: String,
+    },
+    ExtraColumn {
+        table: String,
+        column: String,
+    },
+    Nullability {
+        table: String,
+        column: String,
+        expected_null: bool,
+    },
+    Type {
+        table: String,
+        column: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl Mismatch {
+    pub fn table(&self) -> &str {
+        match self {
+            Mismatch::MissingTable { table }
+            | Mismatch::MissingColumn { table, .. }
+            | Mismatch::ExtraColumn { table, .. }
+            | Mismatch::Nullability { table, .. }
+            | Mismatch::Type { table, .. } => table,
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::MissingTable { table } => write!(f, "table `{table}` is missing"),
+            Mismatch::MissingColumn { table, column } => {
+                write!(f, "column `{table}.{column}` is missing")
+            }
+            Mismatch::ExtraColumn { table, column } => {
+                write!(f, "column `{table}.{column}` is not mapped by the entity")
+            }
+            Mismatch::Nullability {
+                table,
+                column,
+                expected_null,
+            } => {
+                let (expected, actual) = match expected_null {
+                    true => ("NULL", "NOT NULL"),
+                    false => ("NOT NULL", "NULL"),
+                };
+                write!(
+                    f,
+                    "column `{table}.{column}` is {actual}, the entity expects {expected}"
+                )
+            }
+            Mismatch::Type {
+                table,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column `{table}.{column}` has type {actual}, the entity expects {expected}"
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ColumnInfo {
+    column_type: String,
+    notnull: bool,
+}
+
+/// Every entity the application maps, keyed by table name.
+fn entity_tables(schema: &Schema) -> Vec<(String, TableCreateStatement)> {
+    vec![
+        (
+            author::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(author::Entity),
+        ),
+        (
+            article::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(article::Entity),
+        ),
+        (
+            tag::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(tag::Entity),
+        ),
+        (
+            article_tag::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(article_tag::Entity),
+        ),
+        (
+            comment::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(comment::Entity),
+        ),
+        (
+            revision::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(revision::Entity),
+        ),
+        (
+            article_slug::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(article_slug::Entity),
+        ),
+        (
+            api_token::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(api_token::Entity),
+        ),
+        (
+            outbox::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(outbox::Entity),
+        ),
+        (
+            publication::Entity.table_name().to_owned(),
+            schema.create_table_from_entity(publication::Entity),
+        ),
+    ]
+}
+
+async fn table_info(
+    conn: &DatabaseConnection,
+    table: &str,
+) -> Result<BTreeMap<String, ColumnInfo>, DbErr> {
+    let stmt = Statement::from_string(
+        DbBackend::Sqlite,
+        format!("PRAGMA table_info(\"{}\")", table.replace('"', "\"\"")),
+    );
+    conn.query_all(stmt)
+        .await?
+        .iter()
+        .map(|row| {
+            let name = row.try_get::<String>("", "name")?;
+            let info = ColumnInfo {
+                column_type: row.try_get("", "type")?,
+                notnull: row.try_get::<i32>("", "notnull")? != 0,
+            };
+            Ok((name, info))
+        })
+        .collect()
+}
+
+/// SQLite compares declared types by affinity, see
+/// <https://www.sqlite.org/datatype3.html#determination_of_column_affinity>.
+fn affinity(declared: &str) -> &'static str {
+    let declared = declared.to_uppercase();
+    if declared.contains("INT") {
+        "INTEGER"
+    } else if ["CHAR", "CLOB", "TEXT"]
+        .iter()
+        .any(|t| declared.contains(t))
+    {
+        "TEXT"
+    } else if declared.is_empty() || declared.contains("BLOB") {
+        "BLOB"
+    } else if ["REAL", "FLOA", "DOUB"]
+        .iter()
+        .any(|t| declared.contains(t))
+    {
+        "REAL"
+    } else {
+        "NUMERIC"
+    }
+}
+
+/// Returns every difference between the live tables and the entities.
+/// An empty list means the schema matches.
+pub async fn verify(conn: &DatabaseConnection) -> Result<Vec<Mismatch>, DbErr> {
+    if conn.get_database_backend() != DbBackend::Sqlite {
+        return Err(DbErr::Custom(
+            "schema verification only supports SQLite".to_owned(),
+        ));
+    }
+    let expected_db = Database::connect("sqlite::memory:").await?;
+    let tables = entity_tables(&Schema::new(DbBackend::Sqlite));
+    for (_, stmt) in &tables {
+        expected_db
+            .execute(expected_db.get_database_backend().build(stmt))
+            .await?;
+    }
+
+    let mut mismatches = vec![];
+    for (table, _) in &tables {
+        let expected = table_info(&expected_db, table).await?;
+        let actual = table_info(conn, table).await?;
+        if actual.is_empty() {
+            mismatches.push(Mismatch::MissingTable {
+                table: table.clone(),
+            });
+            continue;
+        }
+        for (name, want) in &expected {
+            let Some(have) = actual.get(name) else {
+                mismatches.push(Mismatch::MissingColumn {
+                    table: table.clone(),
+                    column: name.clone(),
+                });
+                continue;
+            };
+            if affinity(&want.column_type) != affinity(&have.column_type) {
+                mismatches.push(Mismatch::Type {
+                    table: table.clone(),
+                    column: name.clone(),
+                    expected: want.column_type.clone(),
+                    actual: have.column_type.clone(),
+                });
+            }
+            if want.notnull != have.notnull {
+                mismatches.push(Mismatch::Nullability {
+                    table: table.clone(),
+                    column: name.clone(),
+                    expected_null: !want.notnull,
+                });
+            }
+        }
+        for name in actual.keys().filter(|name| !expected.contains_key(*name)) {
+            mismatches.push(Mismatch::ExtraColumn {
+                table: table.clone(),
+                column: name.clone(),
+            });
+        }
+    }
+    expected_db.close().await?;
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::affinity;
+
+    #[test]
+    fn affinity_rules() {
+        assert_eq!(affinity("integer"), "INTEGER");
+        assert_eq!(affinity("varchar"), "TEXT");
+        assert_eq!(affinity("text"), "TEXT");
+        assert_eq!(affinity("double"), "REAL");
+        assert_eq!(affinity(""), "BLOB");
+        assert_eq!(affinity("boolean"), "NUMERIC");
+    }
+}
@@ -0,0 +1,98 @@
+//! Turns user input into FTS5 query syntax.
+//!
+//! Input is split into terms and `"quoted phrases"`; a trailing `*` turns a
+//! term or phrase into a prefix query. Every term is re-quoted, so FTS5
+//! operators and column filters are matched as plain words instead of failing
+//! as syntax errors.
+
+use crate::error::{Error, Result};
+
+/// Marks the matches in snippets returned by SQLite, see [`highlight`].
+pub const MATCH_START: char = '\u{2}';
+pub const MATCH_END: char = '\u{3}';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery(String);
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut terms = vec![];
+        let mut rest = input.trim();
+        while !rest.is_empty() {
+            let (term, tail) = match rest.strip_prefix('"') {
+                Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+                None => rest.split_once(char::is_whitespace).unwrap_or((rest, "")),
+            };
+            let (term, prefix) = match (term.strip_suffix('*'), tail.strip_prefix('*')) {
+                (Some(term), _) => (term, true),
+                (None, Some(_)) => (term, true),
+                (None, None) => (term, false),
+            };
+            let term = term.trim_end_matches('*').trim();
+            if !term.is_empty() {
+                let star = if prefix { "*" } else { "" };
+                terms.push(format!("\"{}\"{star}", term.replace('"', "\"\"")));
+            }
+            rest = tail.trim_start_matches('*').trim_start();
+        }
+        match terms.is_empty() {
+            true => Err(Error::Validation("the search query is empty".to_string())),
+            false => Ok(Self(terms.join(" "))),
+        }
+    }
+
+    /// The query in FTS5 syntax, to be bound to `MATCH`.
+    pub fn as_fts(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Escapes a snippet for HTML and wraps the matches in `<mark>`.
+pub fn highlight(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len());
+    for c in snippet.chars() {
+        match c {
+            MATCH_START => html.push_str("<mark>"),
+            MATCH_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{highlight, SearchQuery};
+
+    fn fts(input: &str) -> String {
+        SearchQuery::parse(input).unwrap().as_fts().to_string()
+    }
+
+    #[test]
+    fn terms_phrases_and_prefixes() {
+        assert_eq!(fts("rust  web"), r#""rust" "web""#);
+        assert_eq!(fts(r#""sea orm" poem"#), r#""sea orm" "poem""#);
+        assert_eq!(fts("migr*"), r#""migr"*"#);
+        assert_eq!(fts(r#""full tex"* search"#), r#""full tex"* "search""#);
+    }
+
+    #[test]
+    fn fts_syntax_is_quoted() {
+        assert_eq!(fts("title:rust OR NOT"), r#""title:rust" "OR" "NOT""#);
+        assert_eq!(fts(r#"unbalanced "quote"#), r#""unbalanced" "quote""#);
+        assert!(SearchQuery::parse(" * \"\" ").is_err());
+    }
+
+    #[test]
+    fn snippets_are_escaped() {
+        assert_eq!(
+            highlight("<b>\u{2}rust\u{3}</b> & co"),
+            "&lt;b&gt;<mark>rust</mark>&lt;/b&gt; &amp; co"
+        );
+    }
+}
@@ -1,8 +1,22 @@
 use crate::{
-    domain::article,
-    repositories::{ArticleCreate, ArticleRepositoryTrait, AuthorRepositoryTrait, Repository},
+    auth,
+    diff::{self, Diff},
+    domain::{api_token, article, author, comment, outbox, publication, revision, tag},
+    error::{Error, Result},
+    pagination::{KeysetParams, Page, Params, Slice},
+    policy::{self, Action},
+    repositories::{
+        ArticleCreate, ArticleFilter, ArticleRepositoryTrait, ArticleUpdate, AuthorCreate,
+        AuthorRepositoryTrait, AuthorUpdate, CommentCreate, CommentRepositoryTrait,
+        OutboxRepositoryTrait, OutboxUpdate, PublicationAttempt, PublicationRepositoryTrait,
+        Repository, RevisionRepositoryTrait, StatusUpdate, TagRepositoryTrait, TokenCreate,
+        TokenRepositoryTrait,
+    },
+    search::SearchQuery,
+    slug::slugify,
 };
-use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::ActiveEnum;
 use std::{fmt::Debug, sync::Arc};
 
 #[cfg(test)]
@@ -11,15 +25,328 @@ use poem::async_trait;
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait ArticleServiceTrait: Sync + Send + Debug {
-    async fn create_article(&self, title: &str) -> Result<article::ActiveModel>;
-    async fn list_articles(&self, num_page: i32, page_size: i32) -> Result<Vec<article::Model>>;
-    async fn get_article_by_id(&self, id: i32) -> Result<Option<article::Model>>;
+    async fn create_article(
+        &self,
+        title: &str,
+        content: Option<String>,
+        author_id: Option<i32>,
+        actor: &author::Model,
+    ) -> Result<article::ActiveModel>;
+    /// Listing unpublished articles needs a `viewer` who may edit them.
+    async fn list_articles<'a>(
+        &self,
+        filter: &ArticleFilter,
+        params: Params,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Page<article::Model>>;
+    async fn list_articles_after<'a>(
+        &self,
+        filter: &ArticleFilter,
+        params: KeysetParams,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Slice<article::Model>>;
+    async fn search_articles(&self, q: &str, params: Params) -> Result<Page<article::SearchHit>>;
+    async fn get_article_by_id(&self, id: i32) -> Result<article::Model>;
+    /// Resolves current and old slugs; compare the `slug` of the result to
+    /// tell them apart. Like the other lookups taking a `viewer`, an
+    /// unpublished article is not found unless the viewer may edit it.
+    async fn get_article_by_slug<'a>(
+        &self,
+        slug: &str,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<article::Model>;
+    async fn get_article_with_author<'a>(
+        &self,
+        id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<article::WithAuthor>;
+    /// The article as `actor` is about to edit it: not found if they may not
+    /// read it, forbidden if they may read but not edit it.
+    async fn get_editable_article(&self, id: i32, actor: &author::Model) -> Result<article::Model>;
+    /// Only the published articles of the author.
+    async fn list_articles_by_author(&self, author_id: i32) -> Result<Vec<article::Model>>;
+    async fn update_article(
+        &self,
+        id: i32,
+        update: &ArticleUpdate,
+        actor: &author::Model,
+    ) -> Result<article::Model>;
+    /// Moves the article along the workflow of [`article::Status::can_become`].
+    async fn transition_article(
+        &self,
+        id: i32,
+        status: article::Status,
+        actor: &author::Model,
+    ) -> Result<article::Model>;
+    /// Schedules a draft or reviewed article for publishing, `None` cancels.
+    async fn schedule_article(
+        &self,
+        id: i32,
+        publish_at: Option<DateTime<Utc>>,
+        actor: &author::Model,
+    ) -> Result<article::Model>;
+    /// Publishes the scheduled articles that are due, returning how many.
+    async fn publish_scheduled(&self) -> Result<u64>;
+    async fn delete_article(&self, id: i32, actor: &author::Model) -> Result<()>;
+    async fn list_revisions<'a>(
+        &self,
+        id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<revision::Model>>;
+    async fn get_revision<'a>(
+        &self,
+        id: i32,
+        rev: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<revision::Model>;
+    async fn diff_revisions<'a>(
+        &self,
+        id: i32,
+        from: i32,
+        to: i32,
+        format: diff::Format,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Diff>;
+    /// Writes the title and content of `rev` back as a new revision, made by
+    /// `actor`.
+    async fn restore_revision(
+        &self,
+        id: i32,
+        rev: i32,
+        actor: &author::Model,
+    ) -> Result<article::Model>;
 }
 
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait AuthorServiceTrait: Sync + Send + Debug {
+    async fn create_author(
+        &self,
+        author: &AuthorCreate,
+        actor: &author::Model,
+    ) -> Result<author::ActiveModel>;
+    /// Sets up the first author, as an admin; `Forbidden` once there are authors.
+    async fn create_first_author(&self, author: &AuthorCreate) -> Result<author::ActiveModel>;
+    async fn list_authors(&self, params: Params) -> Result<Page<author::Model>>;
+    async fn get_author_by_id(&self, id: i32) -> Result<author::Model>;
+    /// The author with this email and password, `Unauthorized` otherwise.
+    async fn authenticate(&self, email: &str, password: &str) -> Result<author::Model>;
+    async fn update_author(
+        &self,
+        id: i32,
+        update: &AuthorUpdate,
+        actor: &author::Model,
+    ) -> Result<author::Model>;
+    async fn delete_author(&self, id: i32, actor: &author::Model) -> Result<()>;
+}
+
+/// Tag names are normalized with [`slugify`], so "Rust" and "rust" are one tag.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait TagServiceTrait: Sync + Send + Debug {
+    async fn list_tags(&self) -> Result<Vec<tag::WithCount>>;
+    async fn get_tag(&self, name: &str) -> Result<tag::Model>;
+    async fn list_article_tags<'a>(
+        &self,
+        article_id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<tag::Model>>;
+    async fn tag_article(
+        &self,
+        article_id: i32,
+        name: &str,
+        actor: &author::Model,
+    ) -> Result<tag::Model>;
+    async fn untag_article(&self, article_id: i32, name: &str, actor: &author::Model)
+        -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait CommentServiceTrait: Sync + Send + Debug {
+    /// Like the article lookups, only for articles the `viewer` may read.
+    async fn list_comments<'a>(
+        &self,
+        article_id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<comment::Thread>>;
+    /// Replies must answer a comment on the same article.
+    async fn post_comment<'a>(
+        &self,
+        comment: &CommentCreate,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<comment::Model>;
+    async fn edit_comment(
+        &self,
+        article_id: i32,
+        id: i32,
+        body: &str,
+        actor: &author::Model,
+    ) -> Result<comment::Model>;
+    async fn delete_comment(&self, article_id: i32, id: i32, actor: &author::Model) -> Result<()>;
+}
+
+/// API tokens for machine clients. Only a hash of each secret is stored, so
+/// the secret is shown once, when the token is minted.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait TokenServiceTrait: Sync + Send + Debug {
+    /// The new token and its secret.
+    async fn mint_token(
+        &self,
+        author_id: i32,
+        name: &str,
+        scopes: &[api_token::Scope],
+        expires_in_days: i64,
+    ) -> Result<(api_token::Model, String)>;
+    async fn list_tokens(&self, author_id: i32) -> Result<Vec<api_token::Model>>;
+    async fn revoke_token(&self, author_id: i32, id: i32) -> Result<()>;
+    /// The author and token of a secret, `Unauthorized` when it is unknown or expired.
+    async fn authenticate_token(&self, secret: &str) -> Result<(author::Model, api_token::Model)>;
+}
+
+/// Posts articles to named channels, see [`crate::publishers`].
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait SocialMediaPublisherTrait: Sync + Send + Debug {
-    async fn publish_article(&self, article: &article::ActiveModel) -> Result<()>;
+    fn channels(&self) -> Vec<String>;
+    /// Posts the article to one of the channels, for the first time or again.
+    /// Publishers with a single channel ignore `channel`.
+    async fn publish_to(&self, channel: &str, article: &article::Model) -> Result<Posted>;
+}
+
+/// Where a post ended up, as far as the channel tells.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Posted {
+    pub remote_id: Option<String>,
+    pub remote_url: Option<String>,
+}
+
+/// Where articles were posted, per channel.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait PublicationServiceTrait: Sync + Send + Debug {
+    /// Like the article, hidden unless it is published or `viewer` may edit it.
+    async fn list_publications<'a>(
+        &self,
+        article_id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<publication::Model>>;
+    /// Posts a published article to `channel` right away. A failure is
+    /// recorded on the returned publication.
+    async fn republish_article(
+        &self,
+        article_id: i32,
+        channel: &str,
+        actor: &author::Model,
+    ) -> Result<publication::Model>;
+}
+
+/// Delivers the events in the outbox to the publishers, at least once: an
+/// entry is only marked delivered after the publishers accepted it.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait OutboxServiceTrait: Sync + Send + Debug {
+    /// Tries each due entry once.
+    async fn dispatch_due(&self) -> Result<Dispatched>;
+    async fn list_entries(
+        &self,
+        state: Option<outbox::State>,
+        params: Params,
+        actor: &author::Model,
+    ) -> Result<Page<outbox::Model>>;
+    /// Queues a pending or dead entry for delivery right away, with a fresh
+    /// round of attempts.
+    async fn replay_entry(&self, id: i32, actor: &author::Model) -> Result<outbox::Model>;
+}
+
+/// What [`OutboxServiceTrait::dispatch_due`] did with the due entries.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Dispatched {
+    pub delivered: u64,
+    /// Failed, to be tried again later.
+    pub retrying: u64,
+    /// Failed for the last time.
+    pub dead: u64,
+}
+
+/// An entry is dead after failing this many times.
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 10;
+/// Entries handled per dispatch.
+const DISPATCH_BATCH: u64 = 50;
+/// How long a claimed entry is left to its dispatcher before others may
+/// take it over, in case the dispatcher died.
+const CLAIM_LEASE_MINUTES: i64 = 5;
+
+/// How long to wait after the `attempts`th failure: 30 seconds, doubling
+/// up to an hour.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let doublings = attempts.clamp(1, 8) - 1;
+    (Duration::seconds(30) * (1 << doublings)).min(Duration::hours(1))
+}
+
+fn require(field: &str, value: &str) -> Result<()> {
+    match value.trim().is_empty() {
+        true => Err(Error::Validation(format!("{field} must not be empty"))),
+        false => Ok(()),
+    }
+}
+
+fn require_email(email: &str) -> Result<()> {
+    match email.split_once('@') {
+        Some((user, domain)) if !user.is_empty() && !domain.is_empty() => Ok(()),
+        _ => Err(Error::Validation(format!(
+            "`{email}` is not an email address"
+        ))),
+    }
+}
+
+fn require_password(password: &str) -> Result<()> {
+    match password.chars().count() < auth::MIN_PASSWORD_LEN {
+        true => Err(Error::Validation(format!(
+            "password must have at least {} characters",
+            auth::MIN_PASSWORD_LEN
+        ))),
+        false => Ok(()),
+    }
+}
+
+fn require_new_author(author: &AuthorCreate) -> Result<()> {
+    require("first_name", &author.first_name)?;
+    require("last_name", &author.last_name)?;
+    require_email(&author.email)?;
+    match &author.password {
+        Some(password) => require_password(password),
+        None => Ok(()),
+    }
+}
+
+fn normalize(filter: &ArticleFilter) -> ArticleFilter {
+    ArticleFilter {
+        tag: filter.tag.as_deref().map(slugify),
+        status: filter.status,
+        author_id: filter.author_id,
+    }
+}
+
+/// Narrows a listing of unpublished articles to what `viewer` may see.
+fn listable(filter: &ArticleFilter, viewer: Option<&author::Model>) -> Result<ArticleFilter> {
+    let mut filter = normalize(filter);
+    if filter.status != Some(article::Status::Published) {
+        if let Some(author_id) = policy::unpublished_scope(viewer)? {
+            filter.author_id = Some(author_id);
+        }
+    }
+    Ok(filter)
+}
+
+fn tag_slug(name: &str) -> Result<String> {
+    match slugify(name) {
+        slug if slug.is_empty() => Err(Error::Validation(format!(
+            "`{name}` is not a valid tag name"
+        ))),
+        slug => Ok(slug),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,45 +358,844 @@ impl ArticleServiceSt {
     pub fn new(repo: Arc<dyn Repository>) -> Self {
         Self { repo }
     }
+
+    async fn get_readable_article(
+        &self,
+        id: i32,
+        viewer: Option<&author::Model>,
+    ) -> Result<article::Model> {
+        readable(self.get_article_by_id(id).await?, viewer)
+    }
+
+    async fn find_revision(&self, id: i32, rev: i32) -> Result<revision::Model> {
+        RevisionRepositoryTrait::find_revision(self.repo.as_ref(), id, rev)
+            .await?
+            .ok_or(Error::NotFound("revision"))
+    }
+}
+
+/// Unpublished articles look missing to those who may not see them.
+fn readable(article: article::Model, viewer: Option<&author::Model>) -> Result<article::Model> {
+    match policy::may_read(viewer, &article) {
+        true => Ok(article),
+        false => Err(Error::NotFound("article")),
+    }
+}
+
+/// Looks up an article for the services that hang off one, as [`readable`].
+async fn find_readable(
+    repo: &dyn Repository,
+    id: i32,
+    viewer: Option<&author::Model>,
+) -> Result<article::Model> {
+    let article = ArticleRepositoryTrait::find_by_id(repo, id)
+        .await?
+        .ok_or(Error::NotFound("article"))?;
+    readable(article, viewer)
 }
 
 #[async_trait]
 impl ArticleServiceTrait for ArticleServiceSt {
-    async fn create_article(&self, title: &str) -> Result<article::ActiveModel> {
+    async fn create_article(
+        &self,
+        title: &str,
+        content: Option<String>,
+        author_id: Option<i32>,
+        actor: &author::Model,
+    ) -> Result<article::ActiveModel> {
+        policy::authorize(actor, Action::CreateArticle { author_id })?;
+        require("title", title)?;
         let msg = ArticleCreate {
             title: title.to_string(),
+            content,
+            author_id,
         };
         ArticleRepositoryTrait::create(self.repo.as_ref(), &msg).await
     }
 
-    async fn list_articles(&self, page: i32, page_size: i32) -> Result<Vec<article::Model>> {
-        ArticleRepositoryTrait::find_pages(self.repo.as_ref(), page, page_size).await
+    async fn list_articles<'a>(
+        &self,
+        filter: &ArticleFilter,
+        params: Params,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Page<article::Model>> {
+        let filter = listable(filter, viewer)?;
+        ArticleRepositoryTrait::find_pages(self.repo.as_ref(), &filter, params).await
+    }
+
+    async fn list_articles_after<'a>(
+        &self,
+        filter: &ArticleFilter,
+        params: KeysetParams,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Slice<article::Model>> {
+        let filter = listable(filter, viewer)?;
+        ArticleRepositoryTrait::find_after(self.repo.as_ref(), &filter, params).await
+    }
+
+    async fn search_articles(&self, q: &str, params: Params) -> Result<Page<article::SearchHit>> {
+        let query = SearchQuery::parse(q)?;
+        ArticleRepositoryTrait::search(self.repo.as_ref(), &query, params).await
+    }
+
+    async fn get_article_by_id(&self, id: i32) -> Result<article::Model> {
+        ArticleRepositoryTrait::find_by_id(self.repo.as_ref(), id)
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn get_article_by_slug<'a>(
+        &self,
+        slug: &str,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<article::Model> {
+        let article = ArticleRepositoryTrait::find_by_slug(self.repo.as_ref(), slug)
+            .await?
+            .ok_or(Error::NotFound("article"))?;
+        readable(article, viewer)
     }
 
-    async fn get_article_by_id(&self, id: i32) -> Result<Option<article::Model>> {
-        ArticleRepositoryTrait::find_by_id(self.repo.as_ref(), id).await
+    async fn get_article_with_author<'a>(
+        &self,
+        id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<article::WithAuthor> {
+        let found = ArticleRepositoryTrait::find_with_author(self.repo.as_ref(), id)
+            .await?
+            .ok_or(Error::NotFound("article"))?;
+        Ok(article::WithAuthor {
+            article: readable(found.article, viewer)?,
+            author: found.author,
+        })
+    }
+
+    async fn get_editable_article(&self, id: i32, actor: &author::Model) -> Result<article::Model> {
+        let article = self.get_readable_article(id, Some(actor)).await?;
+        policy::authorize(actor, Action::EditArticle(&article))?;
+        Ok(article)
+    }
+
+    async fn list_articles_by_author(&self, author_id: i32) -> Result<Vec<article::Model>> {
+        let mut articles = ArticleRepositoryTrait::find_by_author(self.repo.as_ref(), author_id)
+            .await?
+            .ok_or(Error::NotFound("author"))?;
+        articles.retain(|article| article.status == article::Status::Published);
+        Ok(articles)
+    }
+
+    async fn update_article(
+        &self,
+        id: i32,
+        update: &ArticleUpdate,
+        actor: &author::Model,
+    ) -> Result<article::Model> {
+        if let Some(title) = &update.title {
+            require("title", title)?;
+        }
+        let article = self.get_article_by_id(id).await?;
+        policy::authorize(actor, Action::EditArticle(&article))?;
+        let update = ArticleUpdate {
+            editor_id: Some(actor.id),
+            ..update.clone()
+        };
+        ArticleRepositoryTrait::update(self.repo.as_ref(), id, &update)
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn transition_article(
+        &self,
+        id: i32,
+        status: article::Status,
+        actor: &author::Model,
+    ) -> Result<article::Model> {
+        use article::Status::*;
+        let article = self.get_article_by_id(id).await?;
+        policy::authorize(actor, Action::transition(&article, status))?;
+        if !article.status.can_become(status) {
+            return Err(Error::Conflict(format!(
+                "a `{}` article can't become `{}`",
+                article.status.to_value(),
+                status.to_value()
+            )));
+        }
+        let update = StatusUpdate {
+            status,
+            published_at: match status {
+                Published => Some(Utc::now()),
+                _ => article.published_at,
+            },
+            // A schedule only applies to articles that are not published yet.
+            publish_at: match status {
+                Draft | InReview => article.publish_at,
+                Published | Archived => None,
+            },
+        };
+        ArticleRepositoryTrait::update_status(self.repo.as_ref(), id, &update)
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn schedule_article(
+        &self,
+        id: i32,
+        publish_at: Option<DateTime<Utc>>,
+        actor: &author::Model,
+    ) -> Result<article::Model> {
+        let article = self.get_article_by_id(id).await?;
+        policy::authorize(actor, Action::PublishArticle(&article))?;
+        if !article.status.can_become(article::Status::Published) {
+            return Err(Error::Conflict(format!(
+                "a `{}` article can't be scheduled",
+                article.status.to_value()
+            )));
+        }
+        if publish_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(Error::Validation(
+                "publish_at must be in the future".to_string(),
+            ));
+        }
+        let update = StatusUpdate {
+            status: article.status,
+            published_at: article.published_at,
+            publish_at,
+        };
+        ArticleRepositoryTrait::update_status(self.repo.as_ref(), id, &update)
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn publish_scheduled(&self) -> Result<u64> {
+        let published = ArticleRepositoryTrait::publish_due(self.repo.as_ref(), Utc::now()).await?;
+        Ok(published.len() as u64)
+    }
+
+    async fn delete_article(&self, id: i32, actor: &author::Model) -> Result<()> {
+        let article = self.get_article_by_id(id).await?;
+        policy::authorize(actor, Action::EditArticle(&article))?;
+        match ArticleRepositoryTrait::delete(self.repo.as_ref(), id).await? {
+            true => Ok(()),
+            false => Err(Error::NotFound("article")),
+        }
+    }
+
+    async fn list_revisions<'a>(
+        &self,
+        id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<revision::Model>> {
+        self.get_readable_article(id, viewer).await?;
+        RevisionRepositoryTrait::find_revisions(self.repo.as_ref(), id)
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn get_revision<'a>(
+        &self,
+        id: i32,
+        rev: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<revision::Model> {
+        self.get_readable_article(id, viewer).await?;
+        self.find_revision(id, rev).await
+    }
+
+    async fn diff_revisions<'a>(
+        &self,
+        id: i32,
+        from: i32,
+        to: i32,
+        format: diff::Format,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Diff> {
+        self.get_readable_article(id, viewer).await?;
+        let from = self.find_revision(id, from).await?;
+        let to = self.find_revision(id, to).await?;
+        Ok(diff::diff(&from, &to, format))
+    }
+
+    async fn restore_revision(
+        &self,
+        id: i32,
+        rev: i32,
+        actor: &author::Model,
+    ) -> Result<article::Model> {
+        let article = self.get_article_by_id(id).await?;
+        policy::authorize(actor, Action::EditArticle(&article))?;
+        let revision = self.find_revision(id, rev).await?;
+        let update = ArticleUpdate {
+            title: Some(revision.title),
+            content: Some(revision.content),
+            editor_id: Some(actor.id),
+        };
+        ArticleRepositoryTrait::update(self.repo.as_ref(), id, &update)
+            .await?
+            .ok_or(Error::NotFound("article"))
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct SocialMediaPublisher;
+pub struct AuthorServiceSt {
+    pub repo: Arc<dyn Repository>,
+}
+
+impl AuthorServiceSt {
+    pub fn new(repo: Arc<dyn Repository>) -> Self {
+        Self { repo }
+    }
+}
 
 #[async_trait]
-impl SocialMediaPublisherTrait for SocialMediaPublisher {
-    async fn publish_article(&self, _article: &article::ActiveModel) -> Result<()> {
-        Ok(())
+impl AuthorServiceTrait for AuthorServiceSt {
+    async fn create_author(
+        &self,
+        author: &AuthorCreate,
+        actor: &author::Model,
+    ) -> Result<author::ActiveModel> {
+        policy::authorize(actor, Action::ManageAuthors)?;
+        require_new_author(author)?;
+        AuthorRepositoryTrait::create(self.repo.as_ref(), author).await
+    }
+
+    async fn create_first_author(&self, author: &AuthorCreate) -> Result<author::ActiveModel> {
+        require_new_author(author)?;
+        if author.password.is_none() {
+            return Err(Error::Validation(
+                "the first author needs a password".to_string(),
+            ));
+        }
+        let admin = AuthorCreate {
+            role: author::Role::Admin,
+            ..author.clone()
+        };
+        AuthorRepositoryTrait::create_first(self.repo.as_ref(), &admin)
+            .await?
+            .ok_or_else(|| Error::Forbidden("only admins create authors".to_string()))
+    }
+
+    async fn list_authors(&self, params: Params) -> Result<Page<author::Model>> {
+        AuthorRepositoryTrait::find_pages(self.repo.as_ref(), params).await
+    }
+
+    async fn get_author_by_id(&self, id: i32) -> Result<author::Model> {
+        AuthorRepositoryTrait::get_by_id(self.repo.as_ref(), id)
+            .await?
+            .ok_or(Error::NotFound("author"))
+    }
+
+    async fn authenticate(&self, email: &str, password: &str) -> Result<author::Model> {
+        let author = AuthorRepositoryTrait::find_by_email(self.repo.as_ref(), email).await?;
+        match author {
+            Some(author)
+                if author
+                    .password_hash
+                    .as_deref()
+                    .is_some_and(|hash| auth::verify_password(hash, password)) =>
+            {
+                Ok(author)
+            }
+            _ => Err(Error::Unauthorized),
+        }
+    }
+
+    async fn update_author(
+        &self,
+        id: i32,
+        update: &AuthorUpdate,
+        actor: &author::Model,
+    ) -> Result<author::Model> {
+        match update.role {
+            Some(_) => policy::authorize(actor, Action::ManageAuthors)?,
+            None => policy::authorize(actor, Action::EditAuthor(id))?,
+        }
+        if let Some(first_name) = &update.first_name {
+            require("first_name", first_name)?;
+        }
+        if let Some(last_name) = &update.last_name {
+            require("last_name", last_name)?;
+        }
+        if let Some(email) = &update.email {
+            require_email(email)?;
+        }
+        if let Some(password) = &update.password {
+            require_password(password)?;
+        }
+        AuthorRepositoryTrait::update(self.repo.as_ref(), id, update)
+            .await?
+            .ok_or(Error::NotFound("author"))
+    }
+
+    async fn delete_author(&self, id: i32, actor: &author::Model) -> Result<()> {
+        policy::authorize(actor, Action::ManageAuthors)?;
+        match AuthorRepositoryTrait::delete(self.repo.as_ref(), id).await? {
+            true => Ok(()),
+            false => Err(Error::NotFound("author")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TagServiceSt {
+    pub repo: Arc<dyn Repository>,
+}
+
+impl TagServiceSt {
+    pub fn new(repo: Arc<dyn Repository>) -> Self {
+        Self { repo }
+    }
+
+    /// Tags are part of the article, so changing them is editing it.
+    async fn authorize_edit(&self, article_id: i32, actor: &author::Model) -> Result<()> {
+        let article = ArticleRepositoryTrait::find_by_id(self.repo.as_ref(), article_id)
+            .await?
+            .ok_or(Error::NotFound("article"))?;
+        policy::authorize(actor, Action::EditArticle(&article))
+    }
+}
+
+#[async_trait]
+impl TagServiceTrait for TagServiceSt {
+    async fn list_tags(&self) -> Result<Vec<tag::WithCount>> {
+        TagRepositoryTrait::find_tags(self.repo.as_ref()).await
+    }
+
+    async fn get_tag(&self, name: &str) -> Result<tag::Model> {
+        TagRepositoryTrait::find_tag(self.repo.as_ref(), &slugify(name))
+            .await?
+            .ok_or(Error::NotFound("tag"))
+    }
+
+    async fn list_article_tags<'a>(
+        &self,
+        article_id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<tag::Model>> {
+        find_readable(self.repo.as_ref(), article_id, viewer).await?;
+        TagRepositoryTrait::find_article_tags(self.repo.as_ref(), article_id)
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn tag_article(
+        &self,
+        article_id: i32,
+        name: &str,
+        actor: &author::Model,
+    ) -> Result<tag::Model> {
+        let slug = tag_slug(name)?;
+        self.authorize_edit(article_id, actor).await?;
+        TagRepositoryTrait::attach(self.repo.as_ref(), article_id, &slug, name.trim())
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn untag_article(
+        &self,
+        article_id: i32,
+        name: &str,
+        actor: &author::Model,
+    ) -> Result<()> {
+        self.authorize_edit(article_id, actor).await?;
+        match TagRepositoryTrait::detach(self.repo.as_ref(), article_id, &slugify(name)).await? {
+            true => Ok(()),
+            false => Err(Error::NotFound("tag")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommentServiceSt {
+    pub repo: Arc<dyn Repository>,
+}
+
+impl CommentServiceSt {
+    pub fn new(repo: Arc<dyn Repository>) -> Self {
+        Self { repo }
+    }
+
+    async fn moderate(&self, article_id: i32, actor: &author::Model) -> Result<()> {
+        let article = ArticleRepositoryTrait::find_by_id(self.repo.as_ref(), article_id)
+            .await?
+            .ok_or(Error::NotFound("article"))?;
+        policy::authorize(actor, Action::ModerateComments(&article))
+    }
+}
+
+#[async_trait]
+impl CommentServiceTrait for CommentServiceSt {
+    async fn list_comments<'a>(
+        &self,
+        article_id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<comment::Thread>> {
+        find_readable(self.repo.as_ref(), article_id, viewer).await?;
+        let comments = CommentRepositoryTrait::find_comments(self.repo.as_ref(), article_id)
+            .await?
+            .ok_or(Error::NotFound("article"))?;
+        Ok(comment::Thread::build(comments))
+    }
+
+    async fn post_comment<'a>(
+        &self,
+        comment: &CommentCreate,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<comment::Model> {
+        require("author_name", &comment.author_name)?;
+        require("body", &comment.body)?;
+        find_readable(self.repo.as_ref(), comment.article_id, viewer).await?;
+        if let Some(parent_id) = comment.parent_id {
+            let parent = CommentRepositoryTrait::find_comment(
+                self.repo.as_ref(),
+                comment.article_id,
+                parent_id,
+            )
+            .await?;
+            if parent.is_none() {
+                return Err(Error::Validation(format!(
+                    "comment {parent_id} does not belong to this article"
+                )));
+            }
+        }
+        CommentRepositoryTrait::create(self.repo.as_ref(), comment).await
+    }
+
+    async fn edit_comment(
+        &self,
+        article_id: i32,
+        id: i32,
+        body: &str,
+        actor: &author::Model,
+    ) -> Result<comment::Model> {
+        require("body", body)?;
+        self.moderate(article_id, actor).await?;
+        CommentRepositoryTrait::update(self.repo.as_ref(), article_id, id, body)
+            .await?
+            .ok_or(Error::NotFound("comment"))
+    }
+
+    async fn delete_comment(&self, article_id: i32, id: i32, actor: &author::Model) -> Result<()> {
+        self.moderate(article_id, actor).await?;
+        match CommentRepositoryTrait::delete(self.repo.as_ref(), article_id, id).await? {
+            true => Ok(()),
+            false => Err(Error::NotFound("comment")),
+        }
+    }
+}
+
+/// Tokens live at most a year.
+pub const MAX_TOKEN_DAYS: i64 = 365;
+
+#[derive(Debug, Clone)]
+pub struct TokenServiceSt {
+    pub repo: Arc<dyn Repository>,
+}
+
+impl TokenServiceSt {
+    pub fn new(repo: Arc<dyn Repository>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl TokenServiceTrait for TokenServiceSt {
+    async fn mint_token(
+        &self,
+        author_id: i32,
+        name: &str,
+        scopes: &[api_token::Scope],
+        expires_in_days: i64,
+    ) -> Result<(api_token::Model, String)> {
+        require("name", name)?;
+        if scopes.is_empty() {
+            return Err(Error::Validation("scopes must not be empty".to_string()));
+        }
+        if !(1..=MAX_TOKEN_DAYS).contains(&expires_in_days) {
+            return Err(Error::Validation(format!(
+                "expires_in_days must be between 1 and {MAX_TOKEN_DAYS}"
+            )));
+        }
+        let mut scopes = scopes.to_vec();
+        scopes.sort();
+        scopes.dedup();
+        let secret = auth::generate_token();
+        let token = TokenCreate {
+            author_id,
+            name: name.trim().to_string(),
+            token_hash: auth::hash_token(&secret),
+            scopes,
+            expires_at: Utc::now() + Duration::days(expires_in_days),
+        };
+        let token = TokenRepositoryTrait::create(self.repo.as_ref(), &token).await?;
+        Ok((token, secret))
+    }
+
+    async fn list_tokens(&self, author_id: i32) -> Result<Vec<api_token::Model>> {
+        TokenRepositoryTrait::find_tokens(self.repo.as_ref(), author_id).await
+    }
+
+    async fn revoke_token(&self, author_id: i32, id: i32) -> Result<()> {
+        match TokenRepositoryTrait::delete(self.repo.as_ref(), author_id, id).await? {
+            true => Ok(()),
+            false => Err(Error::NotFound("token")),
+        }
+    }
+
+    async fn authenticate_token(&self, secret: &str) -> Result<(author::Model, api_token::Model)> {
+        let found =
+            TokenRepositoryTrait::find_by_hash(self.repo.as_ref(), &auth::hash_token(secret))
+                .await?;
+        match found {
+            Some((token, author)) if !token.is_expired(Utc::now()) => Ok((author, token)),
+            _ => Err(Error::Unauthorized),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboxServiceSt {
+    pub repo: Arc<dyn Repository>,
+    pub publisher: Arc<dyn SocialMediaPublisherTrait>,
+}
+
+impl OutboxServiceSt {
+    pub fn new(repo: Arc<dyn Repository>, publisher: Arc<dyn SocialMediaPublisherTrait>) -> Self {
+        Self { repo, publisher }
+    }
+
+    /// Posts to every channel that doesn't have the article yet, so a retry
+    /// only repeats the failed ones.
+    async fn deliver(&self, entry: &outbox::Model) -> Result<()> {
+        match entry.event {
+            outbox::Event::ArticlePublished => {
+                let article =
+                    ArticleRepositoryTrait::find_by_id(self.repo.as_ref(), entry.article_id)
+                        .await?
+                        .ok_or(Error::NotFound("article"))?;
+                let publications =
+                    PublicationRepositoryTrait::find_publications(self.repo.as_ref(), article.id)
+                        .await?
+                        .unwrap_or_default();
+                let mut failures = vec![];
+                for channel in self.publisher.channels() {
+                    if publications
+                        .iter()
+                        .any(|p| p.channel == channel && p.is_current(&article))
+                    {
+                        continue;
+                    }
+                    let publication = post_to_channel(
+                        self.repo.as_ref(),
+                        self.publisher.as_ref(),
+                        &article,
+                        &channel,
+                    )
+                    .await?;
+                    if let (publication::State::Failed, Some(error)) =
+                        (publication.state, publication.last_error)
+                    {
+                        failures.push(error);
+                    }
+                }
+                match failures.is_empty() {
+                    true => Ok(()),
+                    false => Err(Error::Internal(anyhow::anyhow!(failures.join("; ")))),
+                }
+            }
+        }
+    }
+}
+
+/// Posts `article` to `channel` and records how it went.
+async fn post_to_channel(
+    repo: &dyn Repository,
+    publisher: &dyn SocialMediaPublisherTrait,
+    article: &article::Model,
+    channel: &str,
+) -> Result<publication::Model> {
+    let attempt = match publisher.publish_to(channel, article).await {
+        Ok(posted) => PublicationAttempt {
+            article_id: article.id,
+            channel: channel.to_string(),
+            remote_id: posted.remote_id,
+            remote_url: posted.remote_url,
+            error: None,
+        },
+        Err(e) => PublicationAttempt {
+            article_id: article.id,
+            channel: channel.to_string(),
+            error: Some(e.to_string()),
+            ..Default::default()
+        },
+    };
+    PublicationRepositoryTrait::record_attempt(repo, &attempt).await
+}
+
+#[async_trait]
+impl OutboxServiceTrait for OutboxServiceSt {
+    async fn dispatch_due(&self) -> Result<Dispatched> {
+        let mut dispatched = Dispatched::default();
+        let started = Utc::now();
+        let lease_until = started + Duration::minutes(CLAIM_LEASE_MINUTES);
+        for _ in 0..DISPATCH_BATCH {
+            let claimed =
+                OutboxRepositoryTrait::claim_due(self.repo.as_ref(), started, lease_until).await?;
+            let Some(entry) = claimed else {
+                break;
+            };
+            let result = self.deliver(&entry).await;
+            let now = Utc::now();
+            let update = match result {
+                Ok(()) => {
+                    dispatched.delivered += 1;
+                    OutboxUpdate {
+                        state: outbox::State::Delivered,
+                        attempts: entry.attempts,
+                        next_attempt_at: entry.next_attempt_at,
+                        last_error: entry.last_error,
+                        delivered_at: Some(now),
+                    }
+                }
+                Err(e) => {
+                    let attempts = entry.attempts + 1;
+                    let state = match attempts >= MAX_DELIVERY_ATTEMPTS {
+                        true => {
+                            dispatched.dead += 1;
+                            tracing::error!("giving up on outbox entry {}: {e}", entry.id);
+                            outbox::State::Dead
+                        }
+                        false => {
+                            dispatched.retrying += 1;
+                            tracing::warn!("delivering outbox entry {} failed: {e}", entry.id);
+                            outbox::State::Pending
+                        }
+                    };
+                    OutboxUpdate {
+                        state,
+                        attempts,
+                        next_attempt_at: now + retry_delay(attempts),
+                        last_error: Some(e.to_string()),
+                        delivered_at: None,
+                    }
+                }
+            };
+            OutboxRepositoryTrait::update_entry(self.repo.as_ref(), entry.id, &update).await?;
+        }
+        Ok(dispatched)
+    }
+
+    async fn list_entries(
+        &self,
+        state: Option<outbox::State>,
+        params: Params,
+        actor: &author::Model,
+    ) -> Result<Page<outbox::Model>> {
+        policy::authorize(actor, Action::ManageOutbox)?;
+        OutboxRepositoryTrait::find_entries(self.repo.as_ref(), state, params).await
+    }
+
+    async fn replay_entry(&self, id: i32, actor: &author::Model) -> Result<outbox::Model> {
+        policy::authorize(actor, Action::ManageOutbox)?;
+        let entry = OutboxRepositoryTrait::find_entry(self.repo.as_ref(), id)
+            .await?
+            .ok_or(Error::NotFound("outbox entry"))?;
+        if entry.state == outbox::State::Delivered {
+            return Err(Error::Conflict(
+                "a delivered entry can't be replayed".to_string(),
+            ));
+        }
+        let update = OutboxUpdate {
+            state: outbox::State::Pending,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: entry.last_error,
+            delivered_at: None,
+        };
+        OutboxRepositoryTrait::update_entry(self.repo.as_ref(), id, &update)
+            .await?
+            .ok_or(Error::NotFound("outbox entry"))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PublicationServiceSt {
+    pub repo: Arc<dyn Repository>,
+    pub publisher: Arc<dyn SocialMediaPublisherTrait>,
+}
+
+impl PublicationServiceSt {
+    pub fn new(repo: Arc<dyn Repository>, publisher: Arc<dyn SocialMediaPublisherTrait>) -> Self {
+        Self { repo, publisher }
+    }
+}
+
+#[async_trait]
+impl PublicationServiceTrait for PublicationServiceSt {
+    async fn list_publications<'a>(
+        &self,
+        article_id: i32,
+        viewer: Option<&'a author::Model>,
+    ) -> Result<Vec<publication::Model>> {
+        find_readable(self.repo.as_ref(), article_id, viewer).await?;
+        PublicationRepositoryTrait::find_publications(self.repo.as_ref(), article_id)
+            .await?
+            .ok_or(Error::NotFound("article"))
+    }
+
+    async fn republish_article(
+        &self,
+        article_id: i32,
+        channel: &str,
+        actor: &author::Model,
+    ) -> Result<publication::Model> {
+        let article = ArticleRepositoryTrait::find_by_id(self.repo.as_ref(), article_id)
+            .await?
+            .ok_or(Error::NotFound("article"))?;
+        policy::authorize(actor, Action::PublishArticle(&article))?;
+        if article.status != article::Status::Published {
+            return Err(Error::Conflict(format!(
+                "a `{}` article can't be republished",
+                article.status.to_value()
+            )));
+        }
+        if !self.publisher.channels().iter().any(|c| c == channel) {
+            return Err(Error::NotFound("channel"));
+        }
+        post_to_channel(
+            self.repo.as_ref(),
+            self.publisher.as_ref(),
+            &article,
+            channel,
+        )
+        .await
     }
 }
 
 #[cfg(test)]
 pub mod tests {
     use crate::{
-        domain::article,
+        auth,
+        domain::{
+            api_token, article,
+            author::{self, Role},
+            comment, outbox, publication, revision, tag,
+        },
+        error::Error,
+        pagination::{Page, Params},
         repositories::{
-            tests::MockRepository, MockArticleRepositoryTrait, MockAuthorRepositoryTrait,
+            tests::MockRepository, ArticleFilter, ArticleUpdate, AuthorCreate, AuthorUpdate,
+            CommentCreate, MockArticleRepositoryTrait, MockAuthorRepositoryTrait,
+            MockCommentRepositoryTrait, MockOutboxRepositoryTrait, MockPublicationRepositoryTrait,
+            MockRevisionRepositoryTrait, MockTagRepositoryTrait, MockTokenRepositoryTrait,
+        },
+        services::{
+            retry_delay, ArticleServiceSt, ArticleServiceTrait, AuthorServiceSt,
+            AuthorServiceTrait, CommentServiceSt, CommentServiceTrait, Dispatched,
+            MockSocialMediaPublisherTrait, OutboxServiceSt, OutboxServiceTrait, Posted,
+            PublicationServiceSt, PublicationServiceTrait, TagServiceSt, TagServiceTrait,
+            TokenServiceSt, TokenServiceTrait,
         },
-        services::{ArticleServiceSt, ArticleServiceTrait},
     };
+    use chrono::{Duration, Utc};
     use mockall::predicate;
     use sea_orm::{Set, Unchanged};
     use std::sync::Arc;
@@ -82,6 +1208,33 @@ pub mod tests {
         ArticleServiceSt::new(Arc::new(mock_repo))
     }
 
+    fn actor(id: i32, role: Role) -> author::Model {
+        author::Model {
+            id,
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            password_hash: None,
+            role,
+        }
+    }
+
+    /// A draft titled "title"; tests change what they need with struct
+    /// update syntax.
+    pub(crate) fn draft(id: i32, author_id: Option<i32>) -> article::Model {
+        article::Model {
+            id,
+            title: "title".to_string(),
+            slug: "title".to_string(),
+            content: None,
+            content_html: None,
+            author_id,
+            status: article::Status::Draft,
+            published_at: None,
+            publish_at: None,
+        }
+    }
+
     #[tokio::test]
     async fn get_by_id() {
         let mock_author = MockAuthorRepositoryTrait::new();
@@ -91,15 +1244,14 @@ pub mod tests {
             .with(predicate::ge(0))
             .returning(|id| {
                 Ok(Some(article::Model {
-                    id,
-                    title: "title".to_string(),
-                    content: None,
+                    status: article::Status::Published,
+                    ..draft(id, None)
                 }))
             });
 
         let service = mocked_service(mock_article, mock_author);
         let result = service.get_article_by_id(1).await;
-        assert!(result.is_ok() && result.unwrap().unwrap().id == 1);
+        assert!(result.is_ok() && result.unwrap().id == 1);
     }
 
     #[tokio::test]
@@ -110,13 +1262,29 @@ pub mod tests {
             Ok(article::ActiveModel {
                 id: Set(1),
                 title: Set(ac.title.clone()),
+                slug: Set(ac.title.clone()),
                 content: Unchanged(None),
+                content_html: Unchanged(None),
+                author_id: Set(ac.author_id),
+                status: Set(article::Status::Draft),
+                published_at: Unchanged(None),
+                publish_at: Unchanged(None),
             })
         });
 
         let service = mocked_service(mock_article, mock_author);
-        let result = service.create_article("article").await;
-        assert!(result.is_ok() && result.unwrap().title.unwrap() == "article");
+        let author = actor(2, Role::Author);
+        let result = service
+            .create_article("article", None, Some(2), &author)
+            .await;
+        let created = result.unwrap();
+        assert!(created.title.unwrap() == "article" && created.author_id.unwrap() == Some(2));
+        assert!(matches!(
+            service
+                .create_article("article", None, Some(3), &author)
+                .await,
+            Err(Error::Forbidden(_))
+        ));
     }
 
     #[tokio::test]
@@ -125,16 +1293,1086 @@ pub mod tests {
         let mut mock_article = MockArticleRepositoryTrait::new();
         mock_article
             .expect_find_pages()
-            .returning(|_page, _page_size| {
-                Ok(vec![article::Model {
-                    id: 1,
+            .returning(|_filter, params| {
+                let items = vec![article::Model {
                     title: "article1".to_string(),
-                    content: None,
-                }])
+                    slug: "article1".to_string(),
+                    status: article::Status::Published,
+                    ..draft(1, None)
+                }];
+                Ok(Page::new(items, params, 1))
             });
 
         let service = mocked_service(mock_article, mock_author);
-        let result = service.list_articles(0, 10).await;
-        assert!(result.is_ok() && result.unwrap().len() > 0);
+        let result = service
+            .list_articles(
+                &ArticleFilter::default(),
+                Params::default(),
+                Some(&actor(1, Role::Admin)),
+            )
+            .await;
+        assert!(result.is_ok() && !result.unwrap().items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unpublished_listings_need_a_login() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_pages()
+            .withf(|filter, _params| filter.author_id == Some(3))
+            .times(1)
+            .returning(|_filter, params| Ok(Page::new(vec![], params, 0)));
+        let service = mocked_service(mock_article, MockAuthorRepositoryTrait::new());
+        let drafts = ArticleFilter {
+            status: Some(article::Status::Draft),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            service
+                .list_articles(&drafts, Params::default(), None)
+                .await,
+            Err(Error::Unauthorized)
+        ));
+        assert!(matches!(
+            service
+                .list_articles(&drafts, Params::default(), Some(&actor(3, Role::Reader)))
+                .await,
+            Err(Error::Forbidden(_))
+        ));
+        // Authors only see their own.
+        service
+            .list_articles(&drafts, Params::default(), Some(&actor(3, Role::Author)))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn unpublished_articles_are_not_found() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_slug()
+            .returning(|_slug| Ok(Some(draft(1, Some(3)))));
+        mock_article.expect_find_with_author().returning(|id| {
+            Ok(Some(article::WithAuthor {
+                article: draft(id, Some(3)),
+                author: None,
+            }))
+        });
+        let service = mocked_service(mock_article, MockAuthorRepositoryTrait::new());
+
+        assert!(matches!(
+            service.get_article_by_slug("title", None).await,
+            Err(Error::NotFound("article"))
+        ));
+        assert!(matches!(
+            service
+                .get_article_with_author(1, Some(&actor(4, Role::Author)))
+                .await,
+            Err(Error::NotFound("article"))
+        ));
+        let own = service
+            .get_article_with_author(1, Some(&actor(3, Role::Author)))
+            .await
+            .unwrap();
+        assert_eq!(own.article.id, 1);
+        service
+            .get_article_by_slug("title", Some(&actor(5, Role::Editor)))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn editing_needs_the_right_to_edit() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article.expect_find_by_id().returning(|id| {
+            Ok(Some(match id {
+                1 => draft(1, Some(3)),
+                _ => article::Model {
+                    status: article::Status::Published,
+                    ..draft(id, Some(3))
+                },
+            }))
+        });
+        let service = mocked_service(mock_article, MockAuthorRepositoryTrait::new());
+
+        for reader in [actor(4, Role::Reader), actor(4, Role::Author)] {
+            assert!(matches!(
+                service.get_editable_article(1, &reader).await,
+                Err(Error::NotFound("article"))
+            ));
+            assert!(matches!(
+                service.get_editable_article(2, &reader).await,
+                Err(Error::Forbidden(_))
+            ));
+        }
+        let own = service
+            .get_editable_article(1, &actor(3, Role::Author))
+            .await
+            .unwrap();
+        assert_eq!(own.id, 1);
+    }
+
+    #[tokio::test]
+    async fn update_article_missing() {
+        let mock_author = MockAuthorRepositoryTrait::new();
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_id()
+            .with(predicate::eq(42))
+            .returning(|_id| Ok(None));
+
+        let service = mocked_service(mock_article, mock_author);
+        let update = ArticleUpdate {
+            title: Some("title".to_string()),
+            ..Default::default()
+        };
+        let result = service
+            .update_article(42, &update, &actor(1, Role::Admin))
+            .await;
+        assert!(matches!(result, Err(Error::NotFound("article"))));
+    }
+
+    #[tokio::test]
+    async fn updates_are_recorded_as_the_actor() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(draft(id, Some(3)))));
+        mock_article
+            .expect_update()
+            .withf(|_id, update| update.editor_id == Some(3))
+            .times(1)
+            .returning(|id, _update| Ok(Some(draft(id, Some(3)))));
+
+        let service = mocked_service(mock_article, MockAuthorRepositoryTrait::new());
+        let update = ArticleUpdate {
+            title: Some("title".to_string()),
+            editor_id: Some(4),
+            ..Default::default()
+        };
+        service
+            .update_article(1, &update, &actor(3, Role::Author))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn restore_revision_writes_it_back() {
+        let mut mock_revision = MockRevisionRepositoryTrait::new();
+        mock_revision
+            .expect_find_revision()
+            .returning(|article_id, rev| {
+                Ok((rev == 1).then(|| revision::Model {
+                    id: 10,
+                    article_id,
+                    rev,
+                    title: "first".to_string(),
+                    content: Some("text".to_string()),
+                    content_html: None,
+                    editor_id: None,
+                    created_at: Default::default(),
+                }))
+            });
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(draft(id, Some(3)))));
+        mock_article
+            .expect_update()
+            .with(
+                predicate::eq(7),
+                predicate::eq(ArticleUpdate {
+                    title: Some("first".to_string()),
+                    content: Some(Some("text".to_string())),
+                    editor_id: Some(3),
+                }),
+            )
+            .returning(|id, update| {
+                Ok(Some(article::Model {
+                    title: update.title.clone().unwrap(),
+                    slug: "first".to_string(),
+                    content: update.content.clone().unwrap(),
+                    status: article::Status::Published,
+                    ..draft(id, None)
+                }))
+            });
+
+        let mock_repo = MockRepository::new(mock_article, MockAuthorRepositoryTrait::new())
+            .with_revisions(mock_revision);
+        let service = ArticleServiceSt::new(Arc::new(mock_repo));
+        let author = actor(3, Role::Author);
+        let restored = service.restore_revision(7, 1, &author).await.unwrap();
+        assert_eq!(restored.title, "first");
+        assert!(matches!(
+            service.restore_revision(7, 2, &author).await,
+            Err(Error::NotFound("revision"))
+        ));
+        assert!(matches!(
+            service
+                .restore_revision(7, 1, &actor(4, Role::Author))
+                .await,
+            Err(Error::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn status_transitions() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article.expect_find_by_id().returning(|id| {
+            Ok(Some(article::Model {
+                title: "t".to_string(),
+                slug: "t".to_string(),
+                status: article::Status::Archived,
+                ..draft(id, None)
+            }))
+        });
+        mock_article
+            .expect_update_status()
+            .withf(|_, update| {
+                update.status == article::Status::Draft && update.publish_at.is_none()
+            })
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let service = mocked_service(mock_article, MockAuthorRepositoryTrait::new());
+        let editor = actor(1, Role::Editor);
+        assert!(matches!(
+            service
+                .transition_article(1, article::Status::Published, &editor)
+                .await,
+            Err(Error::Conflict(_))
+        ));
+        assert!(matches!(
+            service.schedule_article(1, None, &editor).await,
+            Err(Error::Conflict(_))
+        ));
+        // Reopening an archived article takes an editor.
+        assert!(matches!(
+            service
+                .transition_article(1, article::Status::Draft, &actor(2, Role::Author))
+                .await,
+            Err(Error::Forbidden(_))
+        ));
+        assert!(matches!(
+            service
+                .transition_article(1, article::Status::Draft, &editor)
+                .await,
+            Err(Error::NotFound("article"))
+        ));
+    }
+
+    fn entry(id: i32, article_id: i32, attempts: i32) -> outbox::Model {
+        outbox::Model {
+            id,
+            event: outbox::Event::ArticlePublished,
+            article_id,
+            state: outbox::State::Pending,
+            attempts,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+            created_at: Utc::now(),
+            delivered_at: None,
+        }
+    }
+
+    fn posted(article_id: i32, channel: &str) -> publication::Model {
+        publication::Model {
+            id: 1,
+            article_id,
+            channel: channel.to_string(),
+            state: publication::State::Posted,
+            remote_id: None,
+            remote_url: None,
+            attempts: 1,
+            last_error: None,
+            posted_at: Some(Utc::now()),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn retries_back_off() {
+        let delays = [1, 2, 3, 8, 9, 20].map(|attempts| retry_delay(attempts).num_seconds());
+        assert_eq!(delays, [30, 60, 120, 3600, 3600, 3600]);
+    }
+
+    #[tokio::test]
+    async fn dispatch_retries_and_gives_up() {
+        let published_at = Utc::now() - Duration::hours(1);
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article.expect_find_by_id().returning(move |id| {
+            Ok(Some(article::Model {
+                status: article::Status::Published,
+                published_at: Some(published_at),
+                ..draft(id, Some(1))
+            }))
+        });
+        let mut mock_outbox = MockOutboxRepositoryTrait::new();
+        let mut due = vec![entry(1, 10, 0), entry(2, 20, 3), entry(3, 30, 9)].into_iter();
+        mock_outbox
+            .expect_claim_due()
+            .withf(|now, lease_until| *lease_until > *now)
+            .times(4)
+            .returning(move |_, _| Ok(due.next()));
+        let before = Utc::now();
+        mock_outbox
+            .expect_update_entry()
+            .times(3)
+            .withf(move |id, update| match id {
+                1 => update.state == outbox::State::Delivered && update.delivered_at.is_some(),
+                2 => {
+                    update.state == outbox::State::Pending
+                        && update.attempts == 4
+                        && update.next_attempt_at >= before + Duration::minutes(4)
+                        && update.last_error.as_deref() == Some("the hook is down")
+                }
+                3 => update.state == outbox::State::Dead && update.attempts == 10,
+                _ => false,
+            })
+            .returning(|id, _| Ok(Some(entry(id, 0, 0))));
+        // Article 20 was posted to `social` before, and isn't posted there again.
+        let mut mock_publication = MockPublicationRepositoryTrait::new();
+        mock_publication
+            .expect_find_publications()
+            .returning(move |article_id| {
+                Ok(Some(match article_id {
+                    20 => vec![publication::Model {
+                        posted_at: Some(published_at + Duration::minutes(1)),
+                        ..posted(20, "social")
+                    }],
+                    _ => vec![],
+                }))
+            });
+        mock_publication
+            .expect_record_attempt()
+            .times(5)
+            .returning(|attempt| {
+                Ok(publication::Model {
+                    state: match attempt.error {
+                        Some(_) => publication::State::Failed,
+                        None => publication::State::Posted,
+                    },
+                    last_error: attempt.error.clone(),
+                    ..posted(attempt.article_id, &attempt.channel)
+                })
+            });
+        let mut publisher = MockSocialMediaPublisherTrait::new();
+        publisher
+            .expect_channels()
+            .returning(|| vec!["hook".to_string(), "social".to_string()]);
+        publisher
+            .expect_publish_to()
+            .times(5)
+            .returning(|channel, article| match (channel, article.id) {
+                ("hook", 20 | 30) => Err(Error::Internal(anyhow::anyhow!("the hook is down"))),
+                ("social", 20) => panic!("posted twice"),
+                _ => Ok(Posted::default()),
+            });
+
+        let repo = MockRepository::new(mock_article, MockAuthorRepositoryTrait::new())
+            .with_outbox(mock_outbox)
+            .with_publications(mock_publication);
+        let service = OutboxServiceSt::new(Arc::new(repo), Arc::new(publisher));
+        assert_eq!(
+            service.dispatch_due().await.unwrap(),
+            Dispatched {
+                delivered: 1,
+                retrying: 1,
+                dead: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_outbox_entry() {
+        let mut mock_outbox = MockOutboxRepositoryTrait::new();
+        mock_outbox.expect_find_entry().returning(|id| {
+            Ok(match id {
+                1 => Some(outbox::Model {
+                    state: outbox::State::Dead,
+                    last_error: Some("gone".to_string()),
+                    ..entry(1, 10, 10)
+                }),
+                2 => Some(outbox::Model {
+                    state: outbox::State::Delivered,
+                    ..entry(2, 10, 0)
+                }),
+                _ => None,
+            })
+        });
+        mock_outbox
+            .expect_update_entry()
+            .withf(|id, update| {
+                *id == 1
+                    && update.state == outbox::State::Pending
+                    && update.attempts == 0
+                    && update.last_error.as_deref() == Some("gone")
+            })
+            .times(1)
+            .returning(|id, _| Ok(Some(entry(id, 10, 0))));
+        let repo = MockRepository::new(
+            MockArticleRepositoryTrait::new(),
+            MockAuthorRepositoryTrait::new(),
+        )
+        .with_outbox(mock_outbox);
+        let service = OutboxServiceSt::new(
+            Arc::new(repo),
+            Arc::new(MockSocialMediaPublisherTrait::new()),
+        );
+
+        let admin = actor(1, Role::Admin);
+        assert!(matches!(
+            service.replay_entry(1, &actor(2, Role::Editor)).await,
+            Err(Error::Forbidden(_))
+        ));
+        let replayed = service.replay_entry(1, &admin).await.unwrap();
+        assert_eq!(replayed.state, outbox::State::Pending);
+        assert!(matches!(
+            service.replay_entry(2, &admin).await,
+            Err(Error::Conflict(_))
+        ));
+        assert!(matches!(
+            service.replay_entry(3, &admin).await,
+            Err(Error::NotFound("outbox entry"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn republish_to_a_channel() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article.expect_find_by_id().returning(|id| {
+            Ok(match id {
+                1 => Some(article::Model {
+                    status: article::Status::Published,
+                    published_at: Some(Utc::now()),
+                    ..draft(1, Some(3))
+                }),
+                2 => Some(draft(2, Some(3))),
+                _ => None,
+            })
+        });
+        let mut mock_publication = MockPublicationRepositoryTrait::new();
+        mock_publication
+            .expect_record_attempt()
+            .withf(|attempt| {
+                attempt.article_id == 1
+                    && attempt.channel == "social"
+                    && attempt.remote_id.as_deref() == Some("1090")
+                    && attempt.error.is_none()
+            })
+            .times(1)
+            .returning(|attempt| {
+                Ok(publication::Model {
+                    attempts: 2,
+                    remote_id: attempt.remote_id.clone(),
+                    ..posted(1, "social")
+                })
+            });
+        let mut publisher = MockSocialMediaPublisherTrait::new();
+        publisher
+            .expect_channels()
+            .returning(|| vec!["social".to_string()]);
+        publisher
+            .expect_publish_to()
+            .withf(|channel, article| channel == "social" && article.id == 1)
+            .times(1)
+            .returning(|_, _| {
+                Ok(Posted {
+                    remote_id: Some("1090".to_string()),
+                    remote_url: None,
+                })
+            });
+        let repo = MockRepository::new(mock_article, MockAuthorRepositoryTrait::new())
+            .with_publications(mock_publication);
+        let service = PublicationServiceSt::new(Arc::new(repo), Arc::new(publisher));
+
+        let editor = actor(2, Role::Editor);
+        assert!(matches!(
+            service
+                .republish_article(1, "social", &actor(3, Role::Author))
+                .await,
+            Err(Error::Forbidden(_))
+        ));
+        assert!(matches!(
+            service.republish_article(2, "social", &editor).await,
+            Err(Error::Conflict(_))
+        ));
+        assert!(matches!(
+            service.republish_article(1, "fax", &editor).await,
+            Err(Error::NotFound("channel"))
+        ));
+        assert!(matches!(
+            service.republish_article(4, "social", &editor).await,
+            Err(Error::NotFound("article"))
+        ));
+        let republished = service
+            .republish_article(1, "social", &editor)
+            .await
+            .unwrap();
+        assert_eq!(republished.attempts, 2);
+        assert_eq!(republished.remote_id.as_deref(), Some("1090"));
+    }
+
+    #[tokio::test]
+    async fn blank_title_is_rejected() {
+        let service = mocked_service(
+            MockArticleRepositoryTrait::new(),
+            MockAuthorRepositoryTrait::new(),
+        );
+        let result = service
+            .create_article("  ", None, None, &actor(1, Role::Admin))
+            .await;
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn delete_article() {
+        let mock_author = MockAuthorRepositoryTrait::new();
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_id()
+            .returning(|id| Ok((id != 2).then(|| draft(id, Some(id)))));
+        mock_article
+            .expect_delete()
+            .with(predicate::eq(1))
+            .times(1)
+            .returning(|_id| Ok(true));
+
+        let service = mocked_service(mock_article, mock_author);
+        let author = actor(1, Role::Author);
+        assert!(service.delete_article(1, &author).await.is_ok());
+        assert!(matches!(
+            service.delete_article(2, &author).await,
+            Err(Error::NotFound("article"))
+        ));
+        assert!(matches!(
+            service.delete_article(3, &author).await,
+            Err(Error::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn create_author() {
+        let mock_article = MockArticleRepositoryTrait::new();
+        let mut mock_author = MockAuthorRepositoryTrait::new();
+        mock_author.expect_create().returning(|ac| {
+            Ok(author::ActiveModel {
+                id: Set(1),
+                first_name: Set(ac.first_name.clone()),
+                last_name: Set(ac.last_name.clone()),
+                email: Set(ac.email.clone()),
+                password_hash: Set(None),
+                role: Set(ac.role),
+            })
+        });
+
+        let mock_repo = MockRepository::new(mock_article, mock_author);
+        let service = AuthorServiceSt::new(Arc::new(mock_repo));
+        let new = AuthorCreate {
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            password: None,
+            role: Role::Editor,
+        };
+        let result = service.create_author(&new, &actor(1, Role::Admin)).await;
+        let created = result.unwrap();
+        assert_eq!(created.email.unwrap(), "ada@example.com");
+        assert_eq!(created.role.unwrap(), Role::Editor);
+        assert!(matches!(
+            service.create_author(&new, &actor(1, Role::Editor)).await,
+            Err(Error::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn first_author_becomes_admin() {
+        let mut mock_author = MockAuthorRepositoryTrait::new();
+        let mut existing = 0;
+        mock_author
+            .expect_create_first()
+            .withf(|ac| ac.role == Role::Admin)
+            .times(2)
+            .returning(move |ac| {
+                existing += 1;
+                if existing > 1 {
+                    return Ok(None);
+                }
+                Ok(Some(author::ActiveModel {
+                    id: Set(1),
+                    first_name: Set(ac.first_name.clone()),
+                    last_name: Set(ac.last_name.clone()),
+                    email: Set(ac.email.clone()),
+                    password_hash: Set(Some("hash".to_string())),
+                    role: Set(ac.role),
+                }))
+            });
+
+        let mock_repo = MockRepository::new(MockArticleRepositoryTrait::new(), mock_author);
+        let service = AuthorServiceSt::new(Arc::new(mock_repo));
+        let mut new = AuthorCreate {
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            password: None,
+            role: Role::Reader,
+        };
+        assert!(matches!(
+            service.create_first_author(&new).await,
+            Err(Error::Validation(_))
+        ));
+        new.password = Some("correct horse".to_string());
+        // The mock has no authors at first, and one after.
+        let created = service.create_first_author(&new).await.unwrap();
+        assert_eq!(created.role.unwrap(), Role::Admin);
+        assert!(matches!(
+            service.create_first_author(&new).await,
+            Err(Error::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn create_author_with_bad_email() {
+        let mock_repo = MockRepository::new(
+            MockArticleRepositoryTrait::new(),
+            MockAuthorRepositoryTrait::new(),
+        );
+        let service = AuthorServiceSt::new(Arc::new(mock_repo));
+        let result = service
+            .create_author(
+                &AuthorCreate {
+                    first_name: "Ada".to_string(),
+                    last_name: "Lovelace".to_string(),
+                    email: "ada.example.com".to_string(),
+                    password: None,
+                    role: Role::Author,
+                },
+                &actor(1, Role::Admin),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn list_authors() {
+        let mock_article = MockArticleRepositoryTrait::new();
+        let mut mock_author = MockAuthorRepositoryTrait::new();
+        mock_author
+            .expect_find_pages()
+            .with(predicate::eq(Params::new(Some(1), Some(10))))
+            .returning(|params| {
+                let items = vec![author::Model {
+                    id: 1,
+                    first_name: "Ada".to_string(),
+                    last_name: "Lovelace".to_string(),
+                    email: "ada@example.com".to_string(),
+                    password_hash: None,
+                    role: Role::Author,
+                }];
+                Ok(Page::new(items, params, 1))
+            });
+
+        let mock_repo = MockRepository::new(mock_article, mock_author);
+        let service = AuthorServiceSt::new(Arc::new(mock_repo));
+        let result = service.list_authors(Params::new(Some(1), Some(10))).await;
+        assert!(result.is_ok() && result.unwrap().items.len() == 1);
+    }
+
+    #[tokio::test]
+    async fn authenticate() {
+        let mut mock_author = MockAuthorRepositoryTrait::new();
+        let hash = auth::hash_password("correct horse").unwrap();
+        mock_author.expect_find_by_email().returning(move |email| {
+            Ok(Some(author::Model {
+                id: 1,
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                email: email.to_string(),
+                password_hash: (email != "nopass@example.com").then(|| hash.clone()),
+                role: Role::Author,
+            }))
+        });
+        let mock_repo = MockRepository::new(MockArticleRepositoryTrait::new(), mock_author);
+        let service = AuthorServiceSt::new(Arc::new(mock_repo));
+
+        let found = service
+            .authenticate("ada@example.com", "correct horse")
+            .await;
+        assert_eq!(found.unwrap().id, 1);
+        for (email, password) in [
+            ("ada@example.com", "battery staple"),
+            ("nopass@example.com", "correct horse"),
+        ] {
+            let result = service.authenticate(email, password).await;
+            assert!(matches!(result, Err(Error::Unauthorized)));
+        }
+
+        let result = service
+            .update_author(
+                1,
+                &AuthorUpdate {
+                    password: Some("short".to_string()),
+                    ..Default::default()
+                },
+                &actor(1, Role::Author),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::Validation(_))));
+
+        // Only admins give roles.
+        let result = service
+            .update_author(
+                1,
+                &AuthorUpdate {
+                    role: Some(Role::Admin),
+                    ..Default::default()
+                },
+                &actor(1, Role::Author),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn tokens_are_stored_hashed() {
+        let mut mock_token = MockTokenRepositoryTrait::new();
+        mock_token
+            .expect_create()
+            .withf(|t| {
+                t.author_id == 1
+                    && t.name == "ci"
+                    && t.scopes == [api_token::Scope::ArticlesRead, api_token::Scope::Admin]
+            })
+            .returning(|t| {
+                Ok(api_token::Model {
+                    id: 1,
+                    author_id: t.author_id,
+                    name: t.name.clone(),
+                    token_hash: t.token_hash.clone(),
+                    scopes: api_token::Scope::join(&t.scopes),
+                    created_at: Utc::now(),
+                    expires_at: t.expires_at,
+                })
+            });
+        let service = TokenServiceSt::new(Arc::new(
+            MockRepository::new(
+                MockArticleRepositoryTrait::new(),
+                MockAuthorRepositoryTrait::new(),
+            )
+            .with_tokens(mock_token),
+        ));
+
+        use api_token::Scope::*;
+        let (token, secret) = service
+            .mint_token(1, " ci ", &[Admin, ArticlesRead, Admin], 30)
+            .await
+            .unwrap();
+        assert_eq!(token.token_hash, auth::hash_token(&secret));
+        assert!(token.expires_at > Utc::now() + Duration::days(29));
+
+        for (name, scopes, days) in [
+            ("", &[Admin][..], 30),
+            ("ci", &[][..], 30),
+            ("ci", &[Admin][..], 0),
+            ("ci", &[Admin][..], 366),
+        ] {
+            let result = service.mint_token(1, name, scopes, days).await;
+            assert!(matches!(result, Err(Error::Validation(_))), "{name} {days}");
+        }
+    }
+
+    #[tokio::test]
+    async fn expired_tokens_are_rejected() {
+        let mut mock_token = MockTokenRepositoryTrait::new();
+        mock_token.expect_find_by_hash().returning(|hash| {
+            let expires_at = match hash == auth::hash_token("pa_old") {
+                true => Utc::now() - Duration::days(1),
+                false => Utc::now() + Duration::days(1),
+            };
+            let token = api_token::Model {
+                id: 1,
+                author_id: 1,
+                name: "ci".to_string(),
+                token_hash: hash.to_string(),
+                scopes: "articles:read".to_string(),
+                created_at: Utc::now(),
+                expires_at,
+            };
+            Ok((hash != auth::hash_token("pa_unknown")).then(|| (token, actor(1, Role::Author))))
+        });
+        mock_token
+            .expect_delete()
+            .returning(|_author_id, id| Ok(id == 1));
+        let service = TokenServiceSt::new(Arc::new(
+            MockRepository::new(
+                MockArticleRepositoryTrait::new(),
+                MockAuthorRepositoryTrait::new(),
+            )
+            .with_tokens(mock_token),
+        ));
+
+        let (author, _token) = service.authenticate_token("pa_new").await.unwrap();
+        assert_eq!(author.id, 1);
+        for secret in ["pa_old", "pa_unknown"] {
+            let result = service.authenticate_token(secret).await;
+            assert!(matches!(result, Err(Error::Unauthorized)), "{secret}");
+        }
+        assert!(service.revoke_token(1, 1).await.is_ok());
+        let result = service.revoke_token(1, 2).await;
+        assert!(matches!(result, Err(Error::NotFound("token"))));
+    }
+
+    fn tag_service(mock_tag: MockTagRepositoryTrait) -> TagServiceSt {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(draft(id, Some(1)))));
+        let mock_repo =
+            MockRepository::new(mock_article, MockAuthorRepositoryTrait::new()).with_tags(mock_tag);
+        TagServiceSt::new(Arc::new(mock_repo))
+    }
+
+    #[tokio::test]
+    async fn tags_of_unpublished_articles_are_not_found() {
+        let mut mock_tag = MockTagRepositoryTrait::new();
+        mock_tag
+            .expect_find_article_tags()
+            .times(1)
+            .returning(|_article_id| Ok(Some(vec![])));
+
+        let service = tag_service(mock_tag);
+        assert!(matches!(
+            service.list_article_tags(1, None).await,
+            Err(Error::NotFound("article"))
+        ));
+        let tags = service
+            .list_article_tags(1, Some(&actor(1, Role::Author)))
+            .await
+            .unwrap();
+        assert!(tags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tag_names_are_slugified() {
+        let mut mock_tag = MockTagRepositoryTrait::new();
+        mock_tag
+            .expect_attach()
+            .with(
+                predicate::eq(1),
+                predicate::eq("web-dev"),
+                predicate::eq("Web Dev"),
+            )
+            .returning(|_id, slug, name| {
+                Ok(Some(tag::Model {
+                    id: 1,
+                    slug: slug.to_string(),
+                    name: name.to_string(),
+                }))
+            });
+
+        let service = tag_service(mock_tag);
+        let author = actor(1, Role::Author);
+        let tag = service.tag_article(1, " Web Dev ", &author).await.unwrap();
+        assert_eq!(tag.slug, "web-dev");
+        assert!(matches!(
+            service.tag_article(1, "!!", &author).await,
+            Err(Error::Validation(_))
+        ));
+        assert!(matches!(
+            service
+                .tag_article(1, "Web Dev", &actor(2, Role::Author))
+                .await,
+            Err(Error::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn untag_missing_tag() {
+        let mut mock_tag = MockTagRepositoryTrait::new();
+        mock_tag
+            .expect_detach()
+            .with(predicate::eq(1), predicate::eq("rust"))
+            .returning(|_id, _slug| Ok(false));
+
+        let service = tag_service(mock_tag);
+        assert!(matches!(
+            service
+                .untag_article(1, "Rust", &actor(1, Role::Editor))
+                .await,
+            Err(Error::NotFound("tag"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reply_must_be_on_the_same_article() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article.expect_find_by_id().returning(|id| {
+            Ok(Some(article::Model {
+                status: article::Status::Published,
+                ..draft(id, None)
+            }))
+        });
+        let mut mock_comment = MockCommentRepositoryTrait::new();
+        mock_comment
+            .expect_find_comment()
+            .with(predicate::eq(1), predicate::eq(9))
+            .returning(|_article_id, _id| Ok(None));
+        mock_comment.expect_create().never();
+
+        let mock_repo = MockRepository::new(mock_article, MockAuthorRepositoryTrait::new())
+            .with_comments(mock_comment);
+        let service = CommentServiceSt::new(Arc::new(mock_repo));
+        let reply = CommentCreate {
+            article_id: 1,
+            parent_id: Some(9),
+            author_name: "Ada".to_string(),
+            body: "Agreed".to_string(),
+        };
+        assert!(matches!(
+            service.post_comment(&reply, None).await,
+            Err(Error::Validation(_))
+        ));
+    }
+
+    /// A comment service over a draft of author 3.
+    fn comments_on_a_draft(mock_comment: MockCommentRepositoryTrait) -> CommentServiceSt {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(draft(id, Some(3)))));
+        let mock_repo = MockRepository::new(mock_article, MockAuthorRepositoryTrait::new())
+            .with_comments(mock_comment);
+        CommentServiceSt::new(Arc::new(mock_repo))
+    }
+
+    #[tokio::test]
+    async fn comments_of_unpublished_articles_are_not_found() {
+        let mut mock_comment = MockCommentRepositoryTrait::new();
+        mock_comment
+            .expect_find_comments()
+            .times(1)
+            .returning(|_article_id| Ok(Some(vec![])));
+        let service = comments_on_a_draft(mock_comment);
+
+        for viewer in [None, Some(&actor(4, Role::Author))] {
+            assert!(matches!(
+                service.list_comments(1, viewer).await,
+                Err(Error::NotFound("article"))
+            ));
+        }
+        let threads = service
+            .list_comments(1, Some(&actor(3, Role::Author)))
+            .await
+            .unwrap();
+        assert!(threads.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unpublished_articles_take_no_comments() {
+        let mut mock_comment = MockCommentRepositoryTrait::new();
+        mock_comment.expect_create().never();
+        let service = comments_on_a_draft(mock_comment);
+
+        let comment = CommentCreate {
+            article_id: 1,
+            parent_id: None,
+            author_name: "Eve".to_string(),
+            body: "First!".to_string(),
+        };
+        for viewer in [None, Some(&actor(4, Role::Author))] {
+            assert!(matches!(
+                service.post_comment(&comment, viewer).await,
+                Err(Error::NotFound("article"))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn comments_are_moderated_by_the_article_author() {
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article
+            .expect_find_by_id()
+            .returning(|id| Ok((id == 1).then(|| draft(1, Some(3)))));
+        let mut mock_comment = MockCommentRepositoryTrait::new();
+        mock_comment
+            .expect_update()
+            .times(1)
+            .returning(|article_id, id, body| {
+                Ok(Some(comment::Model {
+                    id,
+                    article_id,
+                    parent_id: None,
+                    author_name: "Ada".to_string(),
+                    body: body.to_string(),
+                    created_at: Default::default(),
+                }))
+            });
+        mock_comment
+            .expect_delete()
+            .times(1)
+            .returning(|_article_id, _id| Ok(false));
+        let mock_repo = MockRepository::new(mock_article, MockAuthorRepositoryTrait::new())
+            .with_comments(mock_comment);
+        let service = CommentServiceSt::new(Arc::new(mock_repo));
+
+        let stranger = actor(4, Role::Author);
+        assert!(matches!(
+            service.edit_comment(1, 2, "spam", &stranger).await,
+            Err(Error::Forbidden(_))
+        ));
+        assert!(matches!(
+            service.delete_comment(1, 2, &stranger).await,
+            Err(Error::Forbidden(_))
+        ));
+        assert!(matches!(
+            service.delete_comment(2, 2, &stranger).await,
+            Err(Error::NotFound("article"))
+        ));
+        let edited = service
+            .edit_comment(1, 2, "Edited", &actor(3, Role::Author))
+            .await
+            .unwrap();
+        assert_eq!(edited.body, "Edited");
+        assert!(matches!(
+            service.delete_comment(1, 2, &actor(5, Role::Editor)).await,
+            Err(Error::NotFound("comment"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn list_comments_as_threads() {
+        let mut mock_comment = MockCommentRepositoryTrait::new();
+        mock_comment.expect_find_comments().returning(|article_id| {
+            let comment = |id, parent_id| comment::Model {
+                id,
+                article_id,
+                parent_id,
+                author_name: "Ada".to_string(),
+                body: "body".to_string(),
+                created_at: Default::default(),
+            };
+            Ok((article_id == 1)
+                .then(|| vec![comment(1, None), comment(2, Some(1)), comment(3, Some(2))]))
+        });
+
+        let mut mock_article = MockArticleRepositoryTrait::new();
+        mock_article.expect_find_by_id().returning(|id| {
+            Ok((id == 1).then(|| article::Model {
+                status: article::Status::Published,
+                ..draft(1, None)
+            }))
+        });
+
+        let mock_repo = MockRepository::new(mock_article, MockAuthorRepositoryTrait::new())
+            .with_comments(mock_comment);
+        let service = CommentServiceSt::new(Arc::new(mock_repo));
+        let threads = service.list_comments(1, None).await.unwrap();
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].replies[0].replies[0].comment.id, 3);
+        assert!(matches!(
+            service.list_comments(2, None).await,
+            Err(Error::NotFound("article"))
+        ));
     }
 }
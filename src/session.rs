@@ -0,0 +1,128 @@
+//! Server-side sessions. Entries are kept in memory, and the browser only
+//! holds the session id, in a cookie signed with `SESSION_KEY`.
+
+use poem::session::{CookieConfig, MemoryStorage, ServerSession, Session};
+use poem::web::cookie::{CookieKey, SameSite};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+pub const COOKIE_NAME: &str = "poem_article_session";
+
+/// The master secret must have at least this many bytes.
+pub const MIN_KEY_LEN: usize = 32;
+
+/// Sessions expire a week after they were last changed.
+pub const SESSION_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Clone)]
+pub struct SessionConfig {
+    key: CookieKey,
+    /// Only send the cookie over HTTPS.
+    pub secure: bool,
+}
+
+impl SessionConfig {
+    /// Derives the signing key from `secret`, or `None` when it is too short.
+    pub fn from_secret(secret: &[u8], secure: bool) -> Option<SessionConfig> {
+        (secret.len() >= MIN_KEY_LEN).then(|| SessionConfig {
+            key: CookieKey::derive_from(secret),
+            secure,
+        })
+    }
+
+    /// A random key, for tests and when no `SESSION_KEY` is set.
+    pub fn generate() -> SessionConfig {
+        SessionConfig {
+            key: CookieKey::generate(),
+            secure: false,
+        }
+    }
+
+    /// Each call starts an empty store, so build it once per server.
+    pub fn middleware(&self) -> ServerSession<MemoryStorage> {
+        let cookie = CookieConfig::signed(self.key.clone())
+            .name(COOKIE_NAME)
+            .secure(self.secure)
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .max_age(SESSION_TTL);
+        ServerSession::new(cookie, MemoryStorage::new())
+    }
+}
+
+impl fmt::Debug for SessionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionConfig")
+            .field("secure", &self.secure)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlashKind {
+    Success,
+    Error,
+}
+
+/// A message for the next page, after a post/redirect/get.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flash {
+    pub kind: FlashKind,
+    pub message: String,
+}
+
+impl Flash {
+    const KEY: &'static str = "flash";
+
+    pub fn success(message: impl Into<String>) -> Flash {
+        Flash {
+            kind: FlashKind::Success,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Flash {
+        Flash {
+            kind: FlashKind::Error,
+            message: message.into(),
+        }
+    }
+
+    /// Replaces any flash that hasn't been shown yet.
+    pub fn set(self, session: &Session) {
+        session.set(Self::KEY, self);
+    }
+
+    /// Removes the flash, so it is shown once.
+    pub fn take(session: &Session) -> Option<Flash> {
+        let flash = session.get(Self::KEY);
+        if flash.is_some() {
+            session.remove(Self::KEY);
+        }
+        flash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flash_is_taken_once() {
+        let session = Session::default();
+        assert_eq!(Flash::take(&session), None);
+        Flash::error("first").set(&session);
+        Flash::success("second").set(&session);
+        assert_eq!(Flash::take(&session), Some(Flash::success("second")));
+        assert_eq!(Flash::take(&session), None);
+    }
+
+    #[test]
+    fn short_secrets_are_rejected() {
+        assert!(SessionConfig::from_secret(&[7; MIN_KEY_LEN - 1], true).is_none());
+        let config = SessionConfig::from_secret(&[7; MIN_KEY_LEN], true).unwrap();
+        assert_eq!(format!("{config:?}"), "SessionConfig { secure: true, .. }");
+    }
+}
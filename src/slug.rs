@@ -0,0 +1,80 @@
+//! URL slugs.
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// Slugs only keep alphanumeric runs and `-`, the rest of a path segment is
+/// escaped.
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-');
+
+/// Lower-cases `text` and joins its alphanumeric runs with `-`, so
+/// "Rust", " rust " and "RUST!" all become `rust`.
+pub fn slugify(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The base slug of an article titled `title`; titles without any letters
+/// or digits fall back to `article`.
+pub fn for_title(title: &str) -> String {
+    match slugify(title) {
+        slug if slug.is_empty() => "article".to_string(),
+        slug => slug,
+    }
+}
+
+/// The `n`th candidate for `base` when earlier ones are taken: `base`,
+/// `base-2`, `base-3`, ...
+pub fn candidate(base: &str, n: u32) -> String {
+    match n {
+        0 | 1 => base.to_string(),
+        n => format!("{base}-{n}"),
+    }
+}
+
+/// Whether `slug` is one of the candidates for `base`.
+pub fn is_candidate(slug: &str, base: &str) -> bool {
+    match slug.strip_prefix(base) {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('-')
+            .and_then(|n| n.parse::<u32>().ok())
+            .is_some_and(|n| n >= 2 && candidate(base, n) == slug),
+        None => false,
+    }
+}
+
+/// `slug` as a URL path segment; non-ASCII letters are percent-encoded.
+pub fn to_path(slug: &str) -> String {
+    utf8_percent_encode(slug, SEGMENT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{candidate, for_title, is_candidate, slugify, to_path};
+
+    #[test]
+    fn slugs() {
+        assert_eq!(slugify("Rust"), "rust");
+        assert_eq!(slugify("  Web  Frameworks! "), "web-frameworks");
+        assert_eq!(slugify("sea-orm / SQLite"), "sea-orm-sqlite");
+        assert_eq!(slugify("Größe"), "größe");
+        assert_eq!(slugify("?!"), "");
+    }
+
+    #[test]
+    fn candidates() {
+        assert_eq!(for_title("Hello, World"), "hello-world");
+        assert_eq!(for_title("?!"), "article");
+        assert_eq!(candidate("hello", 1), "hello");
+        assert_eq!(candidate("hello", 3), "hello-3");
+        assert!(is_candidate("hello", "hello"));
+        assert!(is_candidate("hello-3", "hello"));
+        assert!(!is_candidate("hello-1", "hello"));
+        assert!(!is_candidate("hello-03", "hello"));
+        assert!(!is_candidate("hello-world", "hello"));
+        assert_eq!(to_path("größe-2"), "gr%C3%B6%C3%9Fe-2");
+    }
+}
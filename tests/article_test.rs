@@ -1,17 +1,27 @@
-use poem_article::domain::article;
+use poem_article::domain::{article, author};
+use poem_article::pagination::{KeysetParams, Params};
+use poem_article::repositories::{ArticleFilter, ArticleRepositoryTrait, DbRepository};
 use sea_orm::sea_query::ColumnDef;
 use sea_orm::*;
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_main() -> Result<(), DbErr> {
     let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
     setup_schema(&conn).await?;
     crud_article(&conn).await?;
-    conn.close().await?;
+    let conn = Arc::new(conn);
+    let repo = DbRepository::new(conn.clone());
+    joined_queries(&conn, &repo).await.expect("joined queries");
+    keyset_listing(&conn, &repo).await.expect("keyset listing");
     Ok(())
 }
 
 async fn setup_schema(conn: &DatabaseConnection) -> Result<(), DbErr> {
+    let builder = conn.get_database_backend();
+    let authors = Schema::new(builder).create_table_from_entity(author::Entity);
+    conn.execute(builder.build(&authors)).await?;
+
     let stmt = sea_query::Table::create()
         .table(article::Entity)
         .col(
@@ -22,10 +32,25 @@ async fn setup_schema(conn: &DatabaseConnection) -> Result<(), DbErr> {
                 .primary_key(),
         )
         .col(ColumnDef::new(article::Column::Title).string())
+        .col(
+            ColumnDef::new(article::Column::Slug)
+                .string()
+                .not_null()
+                .default(""),
+        )
         .col(ColumnDef::new(article::Column::Content).string().null())
+        .col(ColumnDef::new(article::Column::ContentHtml).text().null())
+        .col(ColumnDef::new(article::Column::AuthorId).integer().null())
+        .col(
+            ColumnDef::new(article::Column::Status)
+                .string()
+                .not_null()
+                .default("draft"),
+        )
+        .col(ColumnDef::new(article::Column::PublishedAt).timestamp_with_time_zone())
+        .col(ColumnDef::new(article::Column::PublishAt).timestamp_with_time_zone())
         .to_owned();
 
-    let builder = conn.get_database_backend();
     let result = conn.execute(builder.build(&stmt)).await?;
     println!("Created table: {result:?}");
     Ok(())
@@ -34,6 +59,7 @@ async fn setup_schema(conn: &DatabaseConnection) -> Result<(), DbErr> {
 async fn crud_article(conn: &DatabaseConnection) -> Result<(), DbErr> {
     let mut article = article::ActiveModel {
         title: Set("ATitle".to_owned()),
+        slug: Set("atitle".to_owned()),
         ..Default::default()
     }
     .save(conn)
@@ -44,7 +70,13 @@ async fn crud_article(conn: &DatabaseConnection) -> Result<(), DbErr> {
         article::ActiveModel {
             id: Unchanged(1),
             title: Unchanged("ATitle".to_owned()),
+            slug: Unchanged("atitle".to_owned()),
             content: Unchanged(None),
+            content_html: Unchanged(None),
+            author_id: Unchanged(None),
+            status: Unchanged(article::Status::Draft),
+            published_at: Unchanged(None),
+            publish_at: Unchanged(None),
         }
     );
 
@@ -57,9 +89,91 @@ async fn crud_article(conn: &DatabaseConnection) -> Result<(), DbErr> {
         Some(article::Model {
             id: 1,
             title: "BTitle".to_owned(),
+            slug: "atitle".to_owned(),
             content: None,
+            content_html: None,
+            author_id: None,
+            status: article::Status::Draft,
+            published_at: None,
+            publish_at: None,
         })
     );
 
     Ok(())
 }
+
+async fn joined_queries(conn: &DatabaseConnection, repo: &DbRepository) -> anyhow::Result<()> {
+    let ada = author::ActiveModel {
+        first_name: Set("Ada".to_owned()),
+        last_name: Set("Lovelace".to_owned()),
+        email: Set("ada@example.com".to_owned()),
+        role: Set(author::Role::Author),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+    article::ActiveModel {
+        title: Set("Notes".to_owned()),
+        author_id: Set(Some(ada.id)),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    let with_author = repo.find_with_author(2).await?.expect("article 2");
+    assert_eq!(with_author.article.title, "Notes");
+    assert_eq!(with_author.author, Some(ada.clone()));
+
+    let orphan = repo.find_with_author(1).await?.expect("article 1");
+    assert_eq!(orphan.author, None);
+
+    let articles = repo.find_by_author(ada.id).await?.expect("ada's articles");
+    assert_eq!(articles.iter().map(|a| a.id).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(repo.find_by_author(ada.id + 1).await?, None);
+
+    let by_ada = ArticleFilter {
+        author_id: Some(ada.id),
+        ..Default::default()
+    };
+    let page = repo.find_pages(&by_ada, Params::default()).await?;
+    assert_eq!(page.items.iter().map(|a| a.id).collect::<Vec<_>>(), vec![2]);
+    Ok(())
+}
+
+async fn keyset_listing(conn: &DatabaseConnection, repo: &DbRepository) -> anyhow::Result<()> {
+    for title in ["C", "D", "E"] {
+        article::ActiveModel {
+            title: Set(title.to_owned()),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+    }
+
+    let all = ArticleFilter::default();
+    let first = repo
+        .find_after(&all, KeysetParams::new(None, Some(2)))
+        .await?;
+    assert_eq!(
+        first.items.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    let cursor = first.next.expect("more articles");
+
+    // Rows inserted or removed before the cursor don't shift the next slice.
+    article::Entity::delete_by_id(1).exec(conn).await?;
+    let second = repo
+        .find_after(&all, KeysetParams::new(Some(cursor), Some(2)))
+        .await?;
+    assert_eq!(
+        second.items.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![3, 4]
+    );
+
+    let last = repo
+        .find_after(&all, KeysetParams::new(second.next, Some(2)))
+        .await?;
+    assert_eq!(last.items.iter().map(|a| a.id).collect::<Vec<_>>(), vec![5]);
+    assert_eq!(last.next, None);
+    Ok(())
+}
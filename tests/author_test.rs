@@ -0,0 +1,114 @@
+use poem_article::auth;
+use poem_article::domain::author::Role;
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::pagination::Params;
+use poem_article::repositories::{AuthorCreate, AuthorRepositoryTrait, AuthorUpdate, DbRepository};
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+    let repo = DbRepository::new(Arc::new(conn));
+    crud_author(&repo).await?;
+    first_author(&repo).await?;
+    Ok(())
+}
+
+async fn crud_author(repo: &DbRepository) -> anyhow::Result<()> {
+    let created = AuthorRepositoryTrait::create(
+        repo,
+        &AuthorCreate {
+            first_name: "Ada".to_owned(),
+            last_name: "Lovelace".to_owned(),
+            email: "ada@example.com".to_owned(),
+            password: Some("correct horse".to_owned()),
+            role: Role::Editor,
+        },
+    )
+    .await?;
+    let id = created.id.unwrap();
+
+    // Only the hash is stored.
+    let found = AuthorRepositoryTrait::find_by_email(repo, "ada@example.com")
+        .await?
+        .expect("author by email");
+    assert_eq!(found.role, Role::Editor);
+    let hash = found.password_hash.expect("a password hash");
+    assert!(auth::verify_password(&hash, "correct horse"));
+    assert_eq!(
+        AuthorRepositoryTrait::find_by_email(repo, "bob@example.com").await?,
+        None
+    );
+
+    let update = AuthorUpdate {
+        last_name: Some("King".to_owned()),
+        ..Default::default()
+    };
+    let updated = AuthorRepositoryTrait::update(repo, id, &update).await?;
+    assert_eq!(
+        updated.map(|a| (a.first_name, a.last_name)),
+        Some(("Ada".to_owned(), "King".to_owned()))
+    );
+
+    let new_password = AuthorUpdate {
+        password: Some("battery staple".to_owned()),
+        ..Default::default()
+    };
+    let updated = AuthorRepositoryTrait::update(repo, id, &new_password).await?;
+    let hash = updated
+        .and_then(|a| a.password_hash)
+        .expect("a password hash");
+    assert!(auth::verify_password(&hash, "battery staple"));
+
+    let promoted = AuthorUpdate {
+        role: Some(Role::Admin),
+        ..Default::default()
+    };
+    let updated = AuthorRepositoryTrait::update(repo, id, &promoted).await?;
+    assert_eq!(
+        updated.map(|a| (a.role, a.last_name)),
+        Some((Role::Admin, "King".to_owned()))
+    );
+
+    let missing = AuthorRepositoryTrait::update(repo, id + 1, &update).await?;
+    assert_eq!(missing, None);
+
+    let page = AuthorRepositoryTrait::find_pages(repo, Params::new(Some(1), Some(10))).await?;
+    assert_eq!(
+        (page.items.len(), page.total_items, page.total_pages),
+        (1, 1, 1)
+    );
+    let past_end = AuthorRepositoryTrait::find_pages(repo, Params::new(Some(2), Some(10))).await?;
+    assert!(past_end.items.is_empty() && past_end.total_items == 1);
+
+    assert!(AuthorRepositoryTrait::delete(repo, id).await?);
+    assert!(!AuthorRepositoryTrait::delete(repo, id).await?);
+    assert_eq!(AuthorRepositoryTrait::get_by_id(repo, id).await?, None);
+    Ok(())
+}
+
+/// Of two sign-ups at once only one becomes the first author.
+async fn first_author(repo: &DbRepository) -> anyhow::Result<()> {
+    let admin = |email: &str| AuthorCreate {
+        first_name: "Grace".to_owned(),
+        last_name: "Hopper".to_owned(),
+        email: email.to_owned(),
+        password: Some("correct horse".to_owned()),
+        role: Role::Admin,
+    };
+    let (grace, eve) = (admin("grace@example.com"), admin("eve@example.com"));
+    let (first, second) = tokio::join!(repo.create_first(&grace), repo.create_first(&eve));
+    let created: Vec<_> = [first?, second?].into_iter().flatten().collect();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].role.clone().unwrap(), Role::Admin);
+
+    assert!(repo
+        .create_first(&admin("bob@example.com"))
+        .await?
+        .is_none());
+    let page = AuthorRepositoryTrait::find_pages(repo, Params::default()).await?;
+    assert_eq!(page.total_items, 1);
+    Ok(())
+}
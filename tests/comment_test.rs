@@ -0,0 +1,61 @@
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::repositories::{
+    ArticleCreate, ArticleRepositoryTrait, CommentCreate, CommentRepositoryTrait, DbRepository,
+};
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+    let repo = DbRepository::new(Arc::new(conn));
+    let article = ArticleRepositoryTrait::create(
+        &repo,
+        &ArticleCreate {
+            title: "Poem".to_owned(),
+            content: None,
+            author_id: None,
+        },
+    )
+    .await?;
+    let article_id = article.id.unwrap();
+
+    let post = |parent_id: Option<i32>, body: &str| CommentCreate {
+        article_id,
+        parent_id,
+        author_name: "Ada".to_owned(),
+        body: body.to_owned(),
+    };
+    let question = CommentRepositoryTrait::create(&repo, &post(None, "Why?")).await?;
+    let answer = CommentRepositoryTrait::create(&repo, &post(Some(question.id), "Because")).await?;
+    CommentRepositoryTrait::create(&repo, &post(Some(answer.id), "Thanks")).await?;
+    let other = CommentRepositoryTrait::create(&repo, &post(None, "Nice")).await?;
+
+    let comments = repo.find_comments(article_id).await?.expect("article");
+    assert_eq!(comments.len(), 4);
+    assert_eq!(repo.find_comments(article_id + 1).await?, None);
+    assert_eq!(repo.find_comment(article_id + 1, question.id).await?, None);
+
+    let edited = CommentRepositoryTrait::update(&repo, article_id, answer.id, "Because!")
+        .await?
+        .expect("comment");
+    assert_eq!(edited.body, "Because!");
+    assert_eq!(edited.created_at, answer.created_at);
+    assert_eq!(
+        CommentRepositoryTrait::update(&repo, article_id + 1, answer.id, "x").await?,
+        None
+    );
+
+    // Deleting a comment deletes the replies below it.
+    assert!(CommentRepositoryTrait::delete(&repo, article_id, question.id).await?);
+    let comments = repo.find_comments(article_id).await?.expect("article");
+    assert_eq!(
+        comments.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![other.id]
+    );
+
+    assert!(ArticleRepositoryTrait::delete(&repo, article_id).await?);
+    assert_eq!(repo.find_comment(article_id, other.id).await?, None);
+    Ok(())
+}
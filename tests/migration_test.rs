@@ -0,0 +1,51 @@
+use poem_article::migration::{self, Command, Migrator, MigratorTrait};
+use sea_orm::*;
+use sea_orm_migration::MigrationStatus;
+
+#[tokio::test]
+async fn test_main() -> Result<(), DbErr> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+
+    migration::apply_pending(&conn).await?;
+    assert!(Migrator::get_pending_migrations(&conn).await?.is_empty());
+
+    migration::run(&conn, Command::Down).await?;
+    let statuses: Vec<_> = Migrator::get_migration_with_status(&conn)
+        .await?
+        .iter()
+        .map(|m| m.status())
+        .collect();
+    assert_eq!(statuses.last(), Some(&MigrationStatus::Pending));
+    assert_eq!(
+        statuses
+            .iter()
+            .filter(|s| **s == MigrationStatus::Pending)
+            .count(),
+        1
+    );
+
+    migration::run(&conn, Command::Fresh).await?;
+    assert!(Migrator::get_pending_migrations(&conn).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn every_migration_rolls_back() -> Result<(), DbErr> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+
+    Migrator::down(&conn, None).await?;
+    assert_eq!(
+        Migrator::get_pending_migrations(&conn).await?.len(),
+        Migrator::migrations().len()
+    );
+    Migrator::up(&conn, None).await?;
+    assert!(Migrator::get_pending_migrations(&conn).await?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn parse_command() {
+    assert_eq!("status".parse::<Command>(), Ok(Command::Status));
+    assert!("sideways".parse::<Command>().is_err());
+}
@@ -0,0 +1,240 @@
+use chrono::{Duration, Utc};
+use poem::async_trait;
+use poem_article::domain::article::{self, Status};
+use poem_article::domain::author;
+use poem_article::domain::outbox::{self, State};
+use poem_article::domain::publication;
+use poem_article::error::{Error, Result};
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::pagination::Params;
+use poem_article::repositories::{
+    ArticleCreate, ArticleRepositoryTrait, DbRepository, OutboxRepositoryTrait, OutboxUpdate,
+    PublicationRepositoryTrait, StatusUpdate,
+};
+use poem_article::services::{
+    Dispatched, OutboxServiceSt, OutboxServiceTrait, Posted, PublicationServiceSt,
+    PublicationServiceTrait, SocialMediaPublisherTrait,
+};
+use sea_orm::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A reliable `social` channel, and a `hook` that fails its first
+/// `failures` calls.
+#[derive(Debug, Default)]
+struct Flaky {
+    failures: usize,
+    calls: AtomicUsize,
+    published: std::sync::Mutex<Vec<(String, i32)>>,
+}
+
+#[async_trait]
+impl SocialMediaPublisherTrait for Flaky {
+    fn channels(&self) -> Vec<String> {
+        vec!["hook".to_owned(), "social".to_owned()]
+    }
+
+    async fn publish_to(&self, channel: &str, article: &article::Model) -> Result<Posted> {
+        if channel == "hook" && self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(Error::Internal(anyhow::anyhow!("the hook is down")));
+        }
+        let mut published = self.published.lock().unwrap();
+        published.push((channel.to_owned(), article.id));
+        Ok(Posted {
+            remote_id: Some(published.len().to_string()),
+            remote_url: None,
+        })
+    }
+}
+
+fn publish(now: chrono::DateTime<Utc>) -> StatusUpdate {
+    StatusUpdate {
+        status: Status::Published,
+        published_at: Some(now),
+        publish_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+    let repo = Arc::new(DbRepository::new(Arc::new(conn)));
+    let mut ids = vec![];
+    for title in ["Now", "Scheduled", "Deleted"] {
+        let created = ArticleRepositoryTrait::create(
+            repo.as_ref(),
+            &ArticleCreate {
+                title: title.to_owned(),
+                content: None,
+                author_id: None,
+            },
+        )
+        .await?;
+        ids.push(created.id.unwrap());
+    }
+
+    // Publishing queues an event, other changes don't.
+    let now = Utc::now();
+    repo.update_status(ids[0], &publish(now)).await?;
+    let archive = StatusUpdate {
+        status: Status::Archived,
+        published_at: Some(now),
+        publish_at: None,
+    };
+    repo.update_status(ids[0], &archive).await?;
+    let schedule = StatusUpdate {
+        status: Status::InReview,
+        published_at: None,
+        publish_at: Some(now - Duration::minutes(1)),
+    };
+    repo.update_status(ids[1], &schedule).await?;
+    repo.publish_due(now).await?;
+    repo.update_status(ids[2], &publish(now)).await?;
+    ArticleRepositoryTrait::delete(repo.as_ref(), ids[2]).await?;
+
+    let entries = repo.find_entries(None, Params::default()).await?;
+    assert_eq!(
+        entries
+            .items
+            .iter()
+            .map(|e| (e.article_id, e.event, e.state))
+            .collect::<Vec<_>>(),
+        vec![
+            (ids[1], outbox::Event::ArticlePublished, State::Pending),
+            (ids[0], outbox::Event::ArticlePublished, State::Pending),
+        ]
+    );
+
+    // A failed delivery is retried later, then delivered.
+    let publisher = Arc::new(Flaky {
+        failures: 1,
+        ..Default::default()
+    });
+    let service = OutboxServiceSt::new(repo.clone(), publisher.clone());
+    assert_eq!(
+        service.dispatch_due().await?,
+        Dispatched {
+            delivered: 1,
+            retrying: 1,
+            dead: 0,
+        }
+    );
+    assert_eq!(service.dispatch_due().await?, Dispatched::default());
+    let retrying = repo
+        .find_entries(Some(State::Pending), Params::default())
+        .await?;
+    let entry = &retrying.items[0];
+    assert_eq!(entry.attempts, 1);
+    assert_eq!(entry.last_error.as_deref(), Some("the hook is down"));
+    assert!(entry.next_attempt_at > Utc::now() + Duration::seconds(20));
+
+    // Of two dispatchers only one claims the entry, and it stays claimed
+    // until the lease ends.
+    let due_at = entry.next_attempt_at;
+    let lease_until = due_at + Duration::minutes(5);
+    let (first, second) = tokio::join!(
+        repo.claim_due(due_at, lease_until),
+        repo.claim_due(due_at, lease_until)
+    );
+    let claimed = [first?, second?]
+        .into_iter()
+        .flatten()
+        .map(|e| (e.id, e.next_attempt_at))
+        .collect::<Vec<_>>();
+    assert_eq!(claimed, vec![(entry.id, lease_until)]);
+    assert_eq!(
+        repo.claim_due(due_at + Duration::minutes(1), lease_until)
+            .await?,
+        None
+    );
+    let reclaimed = repo
+        .claim_due(lease_until, lease_until + Duration::minutes(5))
+        .await?;
+    assert_eq!(reclaimed.map(|e| e.id), Some(entry.id));
+    let delivered = repo
+        .find_entries(Some(State::Delivered), Params::default())
+        .await?;
+    assert!(delivered.items[0].delivered_at.is_some());
+    assert_eq!(
+        *publisher.published.lock().unwrap(),
+        vec![
+            ("social".to_owned(), ids[0]),
+            ("hook".to_owned(), ids[1]),
+            ("social".to_owned(), ids[1]),
+        ]
+    );
+
+    // Each channel is recorded, and the retry only posts to the failed one.
+    let summary = |publications: Vec<publication::Model>| {
+        publications
+            .into_iter()
+            .map(|p| (p.channel, p.state, p.attempts, p.last_error))
+            .collect::<Vec<_>>()
+    };
+    let failed = repo.find_publications(ids[0]).await?.unwrap();
+    assert_eq!(
+        summary(failed),
+        vec![
+            (
+                "hook".to_owned(),
+                publication::State::Failed,
+                1,
+                Some("the hook is down".to_owned()),
+            ),
+            ("social".to_owned(), publication::State::Posted, 1, None),
+        ]
+    );
+    repo.update_entry(
+        entry.id,
+        &OutboxUpdate {
+            state: State::Pending,
+            attempts: entry.attempts,
+            next_attempt_at: Utc::now(),
+            last_error: entry.last_error.clone(),
+            delivered_at: None,
+        },
+    )
+    .await?;
+    assert_eq!(service.dispatch_due().await?.delivered, 1);
+    assert_eq!(
+        publisher.published.lock().unwrap().last(),
+        Some(&("hook".to_owned(), ids[0]))
+    );
+    let retried = repo.find_publications(ids[0]).await?.unwrap();
+    assert_eq!(
+        summary(retried.clone()),
+        vec![
+            (
+                "hook".to_owned(),
+                publication::State::Posted,
+                2,
+                Some("the hook is down".to_owned()),
+            ),
+            ("social".to_owned(), publication::State::Posted, 1, None),
+        ]
+    );
+    assert_eq!(retried[1].remote_id.as_deref(), Some("1"));
+    assert!(retried.iter().all(|p| p.posted_at.is_some()));
+
+    // Republishing posts again to one channel.
+    let publications = PublicationServiceSt::new(repo.clone(), publisher.clone());
+    let admin = author::Model {
+        id: 1,
+        first_name: "Ada".to_owned(),
+        last_name: "Lovelace".to_owned(),
+        email: "ada@example.com".to_owned(),
+        password_hash: None,
+        role: author::Role::Admin,
+    };
+    let republished = publications
+        .republish_article(ids[1], "social", &admin)
+        .await?;
+    assert_eq!(republished.attempts, 2);
+    assert_eq!(republished.remote_id.as_deref(), Some("5"));
+    assert!(matches!(
+        publications.list_publications(ids[2], None).await,
+        Err(Error::NotFound("article"))
+    ));
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use poem::listener::{Acceptor, Listener, TcpListener};
+use poem::web::{Data, Json};
+use poem::{handler, post, EndpointExt, Request, Route, Server};
+use poem_article::domain::article;
+use poem_article::error::Error;
+use poem_article::publishers::{Mastodon, PublisherRegistry, Webhook};
+use poem_article::services::{Posted, SocialMediaPublisherTrait};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+type Received = Arc<Mutex<Vec<(String, Option<String>, Value)>>>;
+
+/// Records the path, `Authorization` and body of every request, and answers
+/// like the Mastodon API.
+#[handler]
+fn record(req: &Request, Json(body): Json<Value>, received: Data<&Received>) -> Json<Value> {
+    let auth = req
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    received
+        .lock()
+        .unwrap()
+        .push((req.uri().path().to_string(), auth, body));
+    Json(json!({ "id": "1090", "url": "https://social.example/@blog/1090" }))
+}
+
+/// Serves on a free local port, returning its base URL.
+async fn stub(received: Received) -> anyhow::Result<String> {
+    let acceptor = TcpListener::bind("127.0.0.1:0").into_acceptor().await?;
+    let addr = acceptor.local_addr()[0]
+        .as_socket_addr()
+        .cloned()
+        .expect("a socket address");
+    let app = Route::new()
+        .at("/hook", post(record))
+        .at("/api/v1/statuses", post(record))
+        .data(received);
+    tokio::spawn(Server::new_with_acceptor(acceptor).run(app));
+    Ok(format!("http://{addr}"))
+}
+
+fn published() -> article::Model {
+    article::Model {
+        id: 7,
+        title: "Hello".to_owned(),
+        slug: "hello".to_owned(),
+        content: None,
+        content_html: None,
+        author_id: None,
+        status: article::Status::Published,
+        published_at: Some("2026-10-18T12:00:00Z".parse().unwrap()),
+        publish_at: None,
+    }
+}
+
+#[tokio::test]
+async fn posts_to_each_channel() -> anyhow::Result<()> {
+    let received = Received::default();
+    let url = stub(received.clone()).await?;
+    let site = "https://blog.example";
+
+    let mut registry = PublisherRegistry::default();
+    registry.register(
+        "hook",
+        Arc::new(Webhook::new(
+            "hook",
+            &format!("{url}/hook"),
+            Some("s3cret".to_owned()),
+            site,
+        )),
+    );
+    registry.register(
+        "social",
+        Arc::new(Mastodon::new("social", &url, "t0ken", "unlisted", site)),
+    );
+    assert_eq!(registry.channels(), ["hook", "social"]);
+    let posted = registry.publish_to("social", &published()).await?;
+    assert_eq!(
+        posted,
+        Posted {
+            remote_id: Some("1090".to_owned()),
+            remote_url: Some("https://social.example/@blog/1090".to_owned()),
+        }
+    );
+    registry.publish_to("hook", &published()).await?;
+
+    let received = received.lock().unwrap().clone();
+    assert_eq!(
+        received,
+        vec![
+            (
+                "/api/v1/statuses".to_owned(),
+                Some("Bearer t0ken".to_owned()),
+                json!({
+                    "status": "Hello\n\nhttps://blog.example/posts/2026/hello",
+                    "visibility": "unlisted",
+                }),
+            ),
+            (
+                "/hook".to_owned(),
+                Some("Bearer s3cret".to_owned()),
+                json!({
+                    "event": "article.published",
+                    "article": {
+                        "id": 7,
+                        "title": "Hello",
+                        "slug": "hello",
+                        "url": "https://blog.example/posts/2026/hello",
+                        "published_at": "2026-10-18T12:00:00Z",
+                    },
+                }),
+            ),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn failures_name_the_channel() -> anyhow::Result<()> {
+    let received = Received::default();
+    let url = stub(received.clone()).await?;
+
+    let mut registry = PublisherRegistry::default();
+    registry.register(
+        "missing",
+        Arc::new(Webhook::new("missing", &format!("{url}/nowhere"), None, "")),
+    );
+    let err = registry
+        .publish_to("missing", &published())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("`missing`"), "{err}");
+    assert!(matches!(
+        registry.publish_to("elsewhere", &published()).await,
+        Err(Error::NotFound("channel"))
+    ));
+    assert!(received.lock().unwrap().is_empty());
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::repositories::{
+    ArticleCreate, ArticleRepositoryTrait, ArticleUpdate, AuthorCreate, AuthorRepositoryTrait,
+    DbRepository, RevisionRepositoryTrait,
+};
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    // Articles written before revisions existed get a first revision backfilled.
+    Migrator::up(&conn, Some(7)).await?;
+    conn.execute_unprepared("INSERT INTO articles (title, content) VALUES ('Legacy', 'old text')")
+        .await?;
+    Migrator::up(&conn, None).await?;
+    let repo = DbRepository::new(Arc::new(conn));
+
+    let legacy = repo.find_revisions(1).await?.expect("article 1");
+    assert_eq!(legacy.len(), 1);
+    assert_eq!(
+        (legacy[0].rev, legacy[0].content.as_deref()),
+        (1, Some("old text"))
+    );
+    assert_eq!(legacy[0].content_html.as_deref(), Some("<p>old text</p>\n"));
+
+    let ada = AuthorRepositoryTrait::create(
+        &repo,
+        &AuthorCreate {
+            first_name: "Ada".to_owned(),
+            last_name: "Lovelace".to_owned(),
+            email: "ada@example.com".to_owned(),
+            password: None,
+            role: Default::default(),
+        },
+    )
+    .await?
+    .id
+    .unwrap();
+    let id = ArticleRepositoryTrait::create(
+        &repo,
+        &ArticleCreate {
+            title: "Draft".to_owned(),
+            content: None,
+            author_id: Some(ada),
+        },
+    )
+    .await?
+    .id
+    .unwrap();
+    for (title, editor_id) in [("Second", None), ("Third", Some(ada))] {
+        let update = ArticleUpdate {
+            title: Some(title.to_owned()),
+            editor_id,
+            ..Default::default()
+        };
+        ArticleRepositoryTrait::update(&repo, id, &update).await?;
+    }
+
+    let revisions = repo.find_revisions(id).await?.expect("article");
+    assert_eq!(
+        revisions
+            .iter()
+            .map(|r| (r.rev, r.title.as_str(), r.editor_id))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, "Draft", Some(ada)),
+            (2, "Second", None),
+            (3, "Third", Some(ada))
+        ]
+    );
+    assert_eq!(
+        repo.find_revision(id, 2).await?.map(|r| r.title),
+        Some("Second".to_owned())
+    );
+    assert_eq!(repo.find_revision(id, 4).await?, None);
+
+    // Content is rendered once, when the revision is written.
+    let update = ArticleUpdate {
+        content: Some(Some("**bold** <script>x()</script>".to_owned())),
+        ..Default::default()
+    };
+    let updated = ArticleRepositoryTrait::update(&repo, id, &update)
+        .await?
+        .expect("article");
+    let html = Some("<p><strong>bold</strong> </p>\n".to_owned());
+    assert_eq!(updated.content_html, html);
+    assert_eq!(repo.find_revision(id, 4).await?.unwrap().content_html, html);
+    let update = ArticleUpdate {
+        title: Some("Fourth".to_owned()),
+        ..Default::default()
+    };
+    let updated = ArticleRepositoryTrait::update(&repo, id, &update)
+        .await?
+        .expect("article");
+    assert_eq!(updated.content_html, html);
+    assert_eq!(repo.find_revisions(99).await?, None);
+
+    // A failed update leaves no revision behind.
+    let missing = ArticleUpdate {
+        title: Some("Nope".to_owned()),
+        ..Default::default()
+    };
+    assert_eq!(
+        ArticleRepositoryTrait::update(&repo, 99, &missing).await?,
+        None
+    );
+
+    assert!(ArticleRepositoryTrait::delete(&repo, id).await?);
+    assert_eq!(repo.find_revision(id, 1).await?, None);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::schema::{self, Mismatch};
+use sea_orm::*;
+
+#[tokio::test]
+async fn test_main() -> Result<(), DbErr> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+
+    let mismatches = schema::verify(&conn).await?;
+    assert!(mismatches.contains(&Mismatch::MissingTable {
+        table: "articles".to_owned()
+    }));
+
+    // Up to the migration that adds `author_id`, articles still has the
+    // legacy non-null `text` column instead of a nullable `content`, and
+    // none of the columns added later.
+    Migrator::up(&conn, Some(3)).await?;
+    let mismatches: Vec<_> = schema::verify(&conn)
+        .await?
+        .into_iter()
+        .filter(|m| m.table() == "articles")
+        .collect();
+    assert_eq!(
+        mismatches,
+        vec![
+            Mismatch::MissingColumn {
+                table: "articles".to_owned(),
+                column: "content".to_owned(),
+            },
+            Mismatch::MissingColumn {
+                table: "articles".to_owned(),
+                column: "content_html".to_owned(),
+            },
+            Mismatch::MissingColumn {
+                table: "articles".to_owned(),
+                column: "publish_at".to_owned(),
+            },
+            Mismatch::MissingColumn {
+                table: "articles".to_owned(),
+                column: "published_at".to_owned(),
+            },
+            Mismatch::MissingColumn {
+                table: "articles".to_owned(),
+                column: "slug".to_owned(),
+            },
+            Mismatch::MissingColumn {
+                table: "articles".to_owned(),
+                column: "status".to_owned(),
+            },
+            Mismatch::ExtraColumn {
+                table: "articles".to_owned(),
+                column: "text".to_owned(),
+            },
+        ]
+    );
+
+    Migrator::up(&conn, None).await?;
+    assert_eq!(schema::verify(&conn).await?, vec![]);
+    Ok(())
+}
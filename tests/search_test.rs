@@ -0,0 +1,103 @@
+use poem_article::domain::article;
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::pagination::Params;
+use poem_article::repositories::{
+    ArticleCreate, ArticleRepositoryTrait, ArticleUpdate, DbRepository, StatusUpdate,
+};
+use poem_article::search::SearchQuery;
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+    let repo = DbRepository::new(Arc::new(conn));
+    for (title, content) in [
+        (
+            "Poem web framework",
+            "Routing & middleware for <async> Rust",
+        ),
+        (
+            "SeaORM migrations",
+            "Schema changes with sea orm migrations",
+        ),
+        ("Cooking", "A recipe for rust-free pans"),
+    ] {
+        let created = ArticleRepositoryTrait::create(
+            &repo,
+            &ArticleCreate {
+                title: title.to_owned(),
+                content: None,
+                author_id: None,
+            },
+        )
+        .await?;
+        let id = created.id.unwrap();
+        let update = ArticleUpdate {
+            content: Some(Some(content.to_owned())),
+            ..Default::default()
+        };
+        ArticleRepositoryTrait::update(&repo, id, &update).await?;
+        let publish = StatusUpdate {
+            status: article::Status::Published,
+            published_at: Some(chrono::Utc::now()),
+            publish_at: None,
+        };
+        repo.update_status(id, &publish).await?;
+    }
+    // Drafts are not searchable.
+    ArticleRepositoryTrait::create(
+        &repo,
+        &ArticleCreate {
+            title: "Rust draft".to_owned(),
+            content: None,
+            author_id: None,
+        },
+    )
+    .await?;
+
+    let mut hits = search(&repo, "rust").await?;
+    hits.sort();
+    assert_eq!(hits, vec![1, 3]);
+
+    let page = repo
+        .search(&SearchQuery::parse("rust")?, Params::default())
+        .await?;
+    assert_eq!(page.total_items, 2);
+    let poem = page.items.iter().find(|hit| hit.article.id == 1).unwrap();
+    assert_eq!(
+        poem.snippet,
+        "Routing &amp; middleware for &lt;async&gt; <mark>Rust</mark>"
+    );
+
+    // Title matches outrank content matches.
+    assert_eq!(search(&repo, "migrations").await?, vec![2]);
+    assert_eq!(search(&repo, "\"sea orm\"").await?, vec![2]);
+    assert_eq!(search(&repo, "\"orm sea\"").await?, Vec::<i32>::new());
+    assert_eq!(search(&repo, "migr*").await?, vec![2]);
+    assert_eq!(
+        search(&repo, "content:rust NEAR(").await?,
+        Vec::<i32>::new()
+    );
+
+    // The index follows updates and deletes.
+    let update = ArticleUpdate {
+        title: Some("Baking".to_owned()),
+        content: Some(None),
+        ..Default::default()
+    };
+    ArticleRepositoryTrait::update(&repo, 3, &update).await?;
+    assert_eq!(search(&repo, "rust").await?, vec![1]);
+    assert_eq!(search(&repo, "baking").await?, vec![3]);
+    ArticleRepositoryTrait::delete(&repo, 1).await?;
+    assert_eq!(search(&repo, "rust").await?, Vec::<i32>::new());
+    Ok(())
+}
+
+async fn search(repo: &DbRepository, q: &str) -> anyhow::Result<Vec<i32>> {
+    let page = repo
+        .search(&SearchQuery::parse(q)?, Params::default())
+        .await?;
+    Ok(page.items.iter().map(|hit| hit.article.id).collect())
+}
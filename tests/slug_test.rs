@@ -0,0 +1,72 @@
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::repositories::{
+    ArticleCreate, ArticleRepositoryTrait, ArticleUpdate, DbRepository,
+};
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    // Existing articles get slugs from their titles, the oldest one first.
+    Migrator::up(&conn, Some(9)).await?;
+    conn.execute_unprepared("INSERT INTO articles (title) VALUES ('Legacy'), ('legacy!')")
+        .await?;
+    Migrator::up(&conn, None).await?;
+    let repo = DbRepository::new(Arc::new(conn));
+    let slug = |id| {
+        let repo = &repo;
+        async move { repo.find_by_id(id).await.map(|a| a.expect("article").slug) }
+    };
+    assert_eq!(slug(1).await?, "legacy");
+    assert_eq!(slug(2).await?, "legacy-2");
+
+    let mut ids = vec![];
+    for title in ["Hello, World", "hello world"] {
+        let created = ArticleRepositoryTrait::create(
+            &repo,
+            &ArticleCreate {
+                title: title.to_owned(),
+                content: None,
+                author_id: None,
+            },
+        )
+        .await?;
+        ids.push(created.id.unwrap());
+    }
+    assert_eq!(slug(ids[0]).await?, "hello-world");
+    assert_eq!(slug(ids[1]).await?, "hello-world-2");
+
+    let retitle = |title: &str| ArticleUpdate {
+        title: Some(title.to_owned()),
+        ..Default::default()
+    };
+    // A title with the same base keeps its suffix.
+    repo.update(ids[1], &retitle("Hello World!")).await?;
+    assert_eq!(slug(ids[1]).await?, "hello-world-2");
+
+    // Renaming keeps the old slug resolvable, and reserved.
+    repo.update(ids[0], &retitle("Goodbye")).await?;
+    assert_eq!(slug(ids[0]).await?, "goodbye");
+    let moved = repo.find_by_slug("hello-world").await?.expect("old slug");
+    assert_eq!((moved.id, moved.slug.as_str()), (ids[0], "goodbye"));
+    let third = ArticleRepositoryTrait::create(
+        &repo,
+        &ArticleCreate {
+            title: "Hello world".to_owned(),
+            content: None,
+            author_id: None,
+        },
+    )
+    .await?;
+    assert_eq!(third.slug.as_ref(), "hello-world-3");
+
+    // An article can take one of its own old slugs back.
+    repo.update(ids[0], &retitle("Hello world")).await?;
+    assert_eq!(slug(ids[0]).await?, "hello-world");
+    let moved = repo.find_by_slug("goodbye").await?.expect("old slug");
+    assert_eq!(moved.slug, "hello-world");
+
+    assert_eq!(repo.find_by_slug("missing").await?, None);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::pagination::Params;
+use poem_article::repositories::{
+    ArticleCreate, ArticleFilter, ArticleRepositoryTrait, DbRepository, TagRepositoryTrait,
+};
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+    let repo = DbRepository::new(Arc::new(conn));
+    let mut ids = vec![];
+    for title in ["Poem", "SeaORM", "Cooking"] {
+        let created = ArticleRepositoryTrait::create(
+            &repo,
+            &ArticleCreate {
+                title: title.to_owned(),
+                content: None,
+                author_id: None,
+            },
+        )
+        .await?;
+        ids.push(created.id.unwrap());
+    }
+
+    // The first spelling wins; attaching twice is a no-op.
+    let rust = repo.attach(ids[0], "rust", "Rust").await?.expect("tag");
+    let again = repo.attach(ids[0], "rust", "RUST").await?.expect("tag");
+    assert_eq!(again, rust);
+    repo.attach(ids[1], "rust", "rust").await?;
+    repo.attach(ids[1], "orm", "ORM").await?;
+    assert_eq!(repo.attach(999, "rust", "Rust").await?, None);
+
+    let counts: Vec<_> = repo
+        .find_tags()
+        .await?
+        .into_iter()
+        .map(|t| (t.name, t.article_count))
+        .collect();
+    assert_eq!(counts, vec![("ORM".to_owned(), 1), ("Rust".to_owned(), 2)]);
+
+    let filter = ArticleFilter {
+        tag: Some("rust".to_owned()),
+        ..Default::default()
+    };
+    let page = repo.find_pages(&filter, Params::default()).await?;
+    assert_eq!(page.total_items, 2);
+    assert_eq!(
+        page.items.iter().map(|a| a.id).collect::<Vec<_>>(),
+        ids[..2]
+    );
+
+    assert!(repo.detach(ids[0], "rust").await?);
+    assert!(!repo.detach(ids[0], "rust").await?);
+    assert!(!repo.detach(ids[0], "missing").await?);
+    assert_eq!(
+        repo.find_pages(&filter, Params::default())
+            .await?
+            .total_items,
+        1
+    );
+
+    // Deleting an article drops its tag links.
+    assert!(ArticleRepositoryTrait::delete(&repo, ids[1]).await?);
+    let tags = repo.find_tags().await?;
+    assert!(tags.iter().all(|t| t.article_count == 0));
+    assert_eq!(repo.find_article_tags(ids[1]).await?, None);
+    assert_eq!(repo.find_article_tags(ids[2]).await?, Some(vec![]));
+    Ok(())
+}
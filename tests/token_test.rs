@@ -0,0 +1,69 @@
+use poem_article::domain::api_token::Scope;
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::repositories::{AuthorCreate, AuthorRepositoryTrait, DbRepository};
+use poem_article::services::{TokenServiceSt, TokenServiceTrait};
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+    mint_and_revoke(Arc::new(DbRepository::new(Arc::new(conn)))).await?;
+    Ok(())
+}
+
+async fn mint_and_revoke(repo: Arc<DbRepository>) -> anyhow::Result<()> {
+    let mut ids = vec![];
+    for email in ["ada@example.com", "bob@example.com"] {
+        let author = AuthorRepositoryTrait::create(
+            repo.as_ref(),
+            &AuthorCreate {
+                first_name: "Ada".to_owned(),
+                last_name: "Lovelace".to_owned(),
+                email: email.to_owned(),
+                password: None,
+                role: Default::default(),
+            },
+        )
+        .await?;
+        ids.push(author.id.unwrap());
+    }
+    let (ada, bob) = (ids[0], ids[1]);
+    let service = TokenServiceSt::new(repo);
+
+    let (read, read_secret) = service
+        .mint_token(ada, "reader", &[Scope::ArticlesRead], 30)
+        .await?;
+    let (_write, _) = service
+        .mint_token(
+            ada,
+            "writer",
+            &[Scope::ArticlesWrite, Scope::ArticlesRead],
+            7,
+        )
+        .await?;
+    assert_ne!(read.token_hash, read_secret);
+    assert_eq!(read.scopes(), vec![Scope::ArticlesRead]);
+
+    let (author, token) = service.authenticate_token(&read_secret).await?;
+    assert_eq!((author.id, token.id), (ada, read.id));
+    assert!(token.allows(Scope::ArticlesRead) && !token.allows(Scope::ArticlesWrite));
+
+    // Newest first, and only the author's own.
+    let names: Vec<_> = service
+        .list_tokens(ada)
+        .await?
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    assert_eq!(names, ["writer", "reader"]);
+    assert!(service.list_tokens(bob).await?.is_empty());
+
+    // Others can't revoke a token.
+    assert!(service.revoke_token(bob, read.id).await.is_err());
+    service.revoke_token(ada, read.id).await?;
+    assert!(service.authenticate_token(&read_secret).await.is_err());
+    assert_eq!(service.list_tokens(ada).await?.len(), 1);
+    Ok(())
+}
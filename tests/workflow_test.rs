@@ -0,0 +1,78 @@
+use chrono::{Duration, Utc};
+use poem_article::domain::article::Status;
+use poem_article::migration::{Migrator, MigratorTrait};
+use poem_article::pagination::Params;
+use poem_article::repositories::{
+    ArticleCreate, ArticleFilter, ArticleRepositoryTrait, DbRepository, StatusUpdate,
+};
+use sea_orm::*;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_main() -> anyhow::Result<()> {
+    let conn: DatabaseConnection = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&conn, None).await?;
+    let repo = DbRepository::new(Arc::new(conn));
+    let mut ids = vec![];
+    for title in ["Due", "Later", "Unscheduled"] {
+        let created = ArticleRepositoryTrait::create(
+            &repo,
+            &ArticleCreate {
+                title: title.to_owned(),
+                content: None,
+                author_id: None,
+            },
+        )
+        .await?;
+        assert_eq!(created.status.as_ref(), &Status::Draft);
+        ids.push(created.id.unwrap());
+    }
+
+    let now = Utc::now();
+    for (id, publish_at) in [
+        (ids[0], now - Duration::minutes(1)),
+        (ids[1], now + Duration::hours(1)),
+    ] {
+        let schedule = StatusUpdate {
+            status: Status::InReview,
+            published_at: None,
+            publish_at: Some(publish_at),
+        };
+        repo.update_status(id, &schedule).await?;
+    }
+
+    let published = ArticleFilter::published();
+    assert_eq!(
+        repo.find_pages(&published, Params::default())
+            .await?
+            .total_items,
+        0
+    );
+
+    let due = repo.publish_due(now).await?;
+    assert_eq!(due.iter().map(|a| a.id).collect::<Vec<_>>(), vec![ids[0]]);
+    assert_eq!(due[0].status, Status::Published);
+    assert!(repo.publish_due(now).await?.is_empty());
+    let page = repo.find_pages(&published, Params::default()).await?;
+    assert_eq!(
+        page.items.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![ids[0]]
+    );
+    let due = &page.items[0];
+    assert_eq!(due.published_at, Some(now - Duration::minutes(1)));
+    assert_eq!(due.publish_at, None);
+
+    let later = repo.find_by_id(ids[1]).await?.expect("article");
+    assert_eq!((later.status, later.published_at), (Status::InReview, None));
+
+    let drafts = ArticleFilter {
+        status: Some(Status::Draft),
+        ..Default::default()
+    };
+    let page = repo.find_pages(&drafts, Params::default()).await?;
+    assert_eq!(
+        page.items.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![ids[2]]
+    );
+    Ok(())
+}